@@ -0,0 +1,95 @@
+// compares the spatial hash broad phase against testing every bullet against every enemy.
+// run with `cargo bench --bench spatial_hash`.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use rand::random;
+
+// the module's own tests come along with it, but they don't run here
+#[path = "../src/misc/spatial_hash.rs"]
+#[allow(dead_code, unused_imports)]
+mod spatial_hash;
+
+use spatial_hash::{SpatialHash, sweep_circle};
+
+const ENEMY_COUNTS: [usize; 3] = [20, 100, 500];
+const BULLET_COUNTS: [usize; 4] = [100, 1000, 5000, 10000];
+// roughly the area a few waves are spread over
+const ARENA_SIZE: f32 = 3000.0;
+const ENEMY_RADIUS: f32 = 20.0;
+const BULLET_RADIUS: f32 = 5.0;
+// a machine gun bullet over one frame at 30 fps
+const BULLET_STEP: Vec2 = Vec2::new(0.0, 1000.0 / 30.0);
+const ITERATIONS: u32 = 100;
+
+fn random_position() -> Vec2 {
+    (Vec2::new(random(), random()) - 0.5) * ARENA_SIZE
+}
+
+// average time per iteration, and the number of hits from the last one
+fn measure(mut f: impl FnMut() -> usize) -> (Duration, usize) {
+    let mut hits = 0;
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        hits = std::hint::black_box(f());
+    }
+    (start.elapsed() / ITERATIONS, hits)
+}
+
+fn main() {
+    let mut spatial_hash = SpatialHash::default();
+    println!("average of {ITERATIONS} iterations");
+    for enemy_count in ENEMY_COUNTS {
+        let enemies: Vec<(Entity, Vec2)> = (0..enemy_count)
+            .map(|i| (Entity::from_raw(i as u32), random_position()))
+            .collect();
+        for bullet_count in BULLET_COUNTS {
+            let bullets: Vec<Vec2> = (0..bullet_count).map(|_| random_position()).collect();
+
+            let (naive_time, naive_hits) = measure(|| {
+                bullets
+                    .iter()
+                    .map(|bullet| {
+                        enemies
+                            .iter()
+                            .filter(|(_, enemy)| {
+                                sweep_circle(
+                                    *bullet - BULLET_STEP,
+                                    *bullet,
+                                    *enemy,
+                                    BULLET_RADIUS + ENEMY_RADIUS,
+                                )
+                                .is_some()
+                            })
+                            .count()
+                    })
+                    .sum()
+            });
+
+            // the hash is rebuilt every frame in game, so include that in the measurement
+            let (hash_time, hash_hits) = measure(|| {
+                spatial_hash.clear();
+                for (entity, position) in &enemies {
+                    spatial_hash.insert(*entity, *position, ENEMY_RADIUS, Vec2::ZERO);
+                }
+                bullets
+                    .iter()
+                    .map(|bullet| {
+                        spatial_hash
+                            .query_sweep(*bullet - BULLET_STEP, *bullet, BULLET_RADIUS)
+                            .len()
+                    })
+                    .sum()
+            });
+
+            assert_eq!(
+                naive_hits, hash_hits,
+                "broad phase missed or duplicated hits"
+            );
+            println!(
+                "{enemy_count:>4} enemies, {bullet_count:>6} bullets: naive {naive_time:>10.2?}, spatial hash {hash_time:>10.2?}"
+            );
+        }
+    }
+}
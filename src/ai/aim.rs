@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    mods::guns::{WeaponData, WeaponSubtype},
+    userdata::UserData,
+};
+
+// how many refinement steps to take when solving for the intercept time
+const SOLVER_ITERATIONS: usize = 10;
+// intercepts further in the future than this are treated as unreachable
+const MAX_INTERCEPT_TIME: f32 = 5.0;
+// how often a shooter's aim error is rerolled
+const AIM_ERROR_INTERVAL: Duration = Duration::from_millis(600);
+
+pub struct AimSolution {
+    // normalized direction to fire in
+    pub direction: Vec3,
+    // seconds until the projectile reaches the target
+    pub time: f32,
+}
+
+// solves for the direction to fire a projectile in so that it meets a target moving at a constant velocity.
+// projectiles inherit the shooter's velocity, and accelerate with gravity (units per second squared).
+// friction is ignored, since projectile friction is close to 1.
+// returns none when there's no intercept, i.e. the target is outrunning the projectile.
+pub fn solve_intercept(
+    shooter_position: Vec3,
+    shooter_velocity: Vec3,
+    muzzle_speed: f32,
+    gravity: Vec3,
+    target_position: Vec3,
+    target_velocity: Vec3,
+) -> Option<AimSolution> {
+    let relative_position = target_position - shooter_position;
+    if !muzzle_speed.is_finite() {
+        // hitscan, aim straight at the target
+        return Some(AimSolution {
+            direction: relative_position.normalize_or_zero(),
+            time: 0.0,
+        });
+    }
+    if muzzle_speed <= 0.0 {
+        return None;
+    }
+    let relative_velocity = target_velocity - shooter_velocity;
+
+    // the muzzle velocity needs to cover this offset by time t, with the drop from gravity compensated for.
+    let offset_at = |t: f32| relative_position + relative_velocity * t - 0.5 * gravity * t * t;
+
+    // fixed point iteration on the flight time, starting from the time to reach the target where it is now.
+    let mut time = relative_position.length() / muzzle_speed;
+    for _ in 0..SOLVER_ITERATIONS {
+        time = offset_at(time).length() / muzzle_speed;
+        if time > MAX_INTERCEPT_TIME {
+            return None;
+        }
+    }
+    // if the iteration didn't settle, there's no reliable solution
+    let offset = offset_at(time);
+    if (offset.length() / muzzle_speed - time).abs() > 0.01 {
+        return None;
+    }
+
+    Some(AimSolution {
+        direction: offset.normalize_or_zero(),
+        time,
+    })
+}
+
+// muzzle speed and per second gravity for a weapon's projectiles.
+// bullet gravity is added to the velocity once per frame, so it's scaled by the frame time.
+pub fn projectile_parameters(weapon: &WeaponData, delta_secs: f32) -> (f32, Vec3) {
+    match weapon.subtype {
+        WeaponSubtype::BulletBased {
+            velocity, gravity, ..
+        } => {
+            let gravity = if delta_secs > 0.0 {
+                gravity / delta_secs
+            } else {
+                Vec3::ZERO
+            };
+            (velocity.length(), gravity)
+        }
+        WeaponSubtype::Laser { .. } => (f32::INFINITY, Vec3::ZERO),
+    }
+}
+
+// angle, in radians, added to a shooter's aim. the maximum error comes from the difficulty setting.
+#[derive(Component)]
+pub struct AimError {
+    pub offset: f32,
+    timer: Timer,
+}
+
+impl Default for AimError {
+    fn default() -> Self {
+        AimError {
+            offset: 0.0,
+            // finish immediately so the first offset gets rolled on the first frame
+            timer: Timer::new(Duration::ZERO, TimerMode::Once),
+        }
+    }
+}
+
+impl AimError {
+    // rotates an aim direction by the current error
+    pub fn apply(&self, direction: Vec3) -> Vec3 {
+        Quat::from_rotation_z(self.offset) * direction
+    }
+}
+
+pub fn aim_error_system(time: Res<Time>, userdata: Res<UserData>, mut query: Query<&mut AimError>) {
+    let max_error = userdata.difficulty.aim_error();
+    for mut aim_error in query.iter_mut() {
+        if aim_error.timer.tick(time.delta()).finished() {
+            aim_error.offset = (rand::random::<f32>() * 2.0 - 1.0) * max_error;
+            aim_error.timer = Timer::new(AIM_ERROR_INTERVAL, TimerMode::Once);
+        }
+    }
+}
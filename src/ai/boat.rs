@@ -0,0 +1,107 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::{
+    body_type_stats::BoatStats, enemy::Turret, input::Intent, mods::guns::WeaponData,
+    physics::Physics, player::Player,
+};
+
+use super::{
+    AI, AIType,
+    aim::{AimError, projectile_parameters, solve_intercept},
+};
+
+// boats try to stay close to underneath the player, but not directly under them.
+const BOAT_FOLLOW_DISTANCE: f32 = 150.0;
+// turrets can only aim above the horizon, relative to what they're mounted on.
+const TURRET_MAX_ANGLE: f32 = FRAC_PI_2 - 0.2;
+const TURRET_FIRE_ARC: f32 = 0.15;
+const TURRET_RANGE: f32 = 800.0;
+
+pub fn boat_ai(
+    mut query: Query<(&mut Intent, &Transform, &AI), Without<Player>>,
+    player: Query<&Transform, With<Player>>,
+) -> Result<(), BevyError> {
+    let player_position = player.single()?.translation;
+    for (mut intent, transform, ai) in query.iter_mut() {
+        if ai.ai_type != AIType::Boat {
+            continue;
+        }
+
+        let dx = player_position.x - transform.translation.x;
+
+        // for boats, turn_intent is the horizontal drive direction.
+        // positive is left, matching planes where a positive turn intent turns counterclockwise.
+        intent.turn_intent = -dx.signum();
+        intent.accelerate = dx.abs() > BOAT_FOLLOW_DISTANCE;
+        intent.brake = !intent.accelerate;
+    }
+    Ok(())
+}
+
+// turrets rotate to lead the player independently of whatever they're mounted on, and fire once they're lined up.
+pub fn turret_aim_system(
+    time: Res<Time>,
+    mut turrets: Query<
+        (
+            &mut Turret,
+            &mut Transform,
+            &GlobalTransform,
+            &mut Intent,
+            &WeaponData,
+            &AimError,
+            &ChildOf,
+        ),
+        Without<Player>,
+    >,
+    parents: Query<(&BoatStats, &GlobalTransform, &Physics)>,
+    player: Query<(&Transform, &Physics), With<Player>>,
+) -> Result<(), BevyError> {
+    let (player_transform, player_physics) = player.single()?;
+    let player_position = player_transform.translation;
+    for (mut turret, mut transform, global_transform, mut intent, weapon, aim_error, child_of) in
+        turrets.iter_mut()
+    {
+        let Ok((stats, parent_transform, parent_physics)) = parents.get(child_of.parent()) else {
+            continue;
+        };
+
+        let position = global_transform.translation();
+        let to_player = player_position - position;
+        let (muzzle_speed, gravity) = projectile_parameters(weapon, time.delta_secs());
+        let solution = solve_intercept(
+            position,
+            parent_physics.velocity,
+            muzzle_speed,
+            gravity,
+            player_position,
+            player_physics.velocity,
+        );
+        let aim = match &solution {
+            Some(solution) => aim_error.apply(solution.direction),
+            None => to_player,
+        };
+
+        // put the direction in the parent's frame of reference, since the turret's rotation is relative to it
+        let (_, parent_rotation, _) = parent_transform.to_scale_rotation_translation();
+        let local = parent_rotation.inverse() * aim;
+
+        // angle from straight up, counterclockwise
+        let target_angle = f32::atan2(-local.x, local.y);
+        let clamped_angle = target_angle.clamp(-TURRET_MAX_ANGLE, TURRET_MAX_ANGLE);
+
+        let max_step = stats.aim_speed * time.delta_secs();
+        turret.angle += (clamped_angle - turret.angle).clamp(-max_step, max_step);
+        transform.rotation = Quat::from_rotation_z(turret.angle);
+
+        // only fire if the shot arrives before the projectile expires
+        intent.fire = solution
+            .as_ref()
+            .is_some_and(|solution| solution.time < weapon.lifetime.as_secs_f32())
+            && target_angle == clamped_angle
+            && (target_angle - turret.angle).abs() < TURRET_FIRE_ARC
+            && to_player.length_squared() < TURRET_RANGE * TURRET_RANGE;
+    }
+    Ok(())
+}
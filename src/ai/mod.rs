@@ -1,10 +1,20 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-pub mod basic;
+pub mod aim;
+pub mod boat;
+pub mod state_machine;
+pub mod submarine;
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+// basic, ace and lancer enemies are driven by state machines configured in ai.ron.
+// boats and submarines move along the water, so they have their own systems.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
 pub enum AIType {
     Basic,
+    Boat,
+    Ace,
+    Submarine,
+    Lancer,
 }
 
 #[derive(Component)]
@@ -26,3 +36,12 @@ impl AI {
     //     }
     // }
 }
+
+// turn intent that rotates `forward` towards `desired`. positive turns counterclockwise.
+pub fn steer_towards(forward: Vec3, desired: Vec3) -> f32 {
+    if desired.truncate().length_squared() == 0.0 {
+        return 0.0;
+    }
+    let signed_angle = forward.truncate().angle_to(desired.truncate());
+    (signed_angle * 3.0).clamp(-1.0, 1.0)
+}
@@ -0,0 +1,410 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{platform::collections::HashMap, prelude::*, reflect::TypePath};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::GameConfig,
+    input::Intent,
+    misc::HP,
+    mods::guns::WeaponData,
+    physics::Physics,
+    player::{Player, TurnAngle},
+};
+
+use super::{
+    AI, AIType,
+    aim::{AimError, projectile_parameters, solve_intercept},
+    steer_towards,
+};
+
+// data driven state machine for plane enemies.
+// each archetype (AIType) has a behaviour, loaded from ai.ron, listing the states it can be in and when to switch between them.
+// the current state decides what gets written to the entity's Intent.
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum AIState {
+    // circle around the spawn point
+    Patrol,
+    // close in on the player, or get behind them if the behaviour has a follow distance
+    Pursue,
+    // aim at where the player is going to be and fire
+    Attack,
+    // break perpendicular to the player's line of fire
+    Evade,
+    // run directly away from the player
+    Flee,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Condition {
+    Always,
+    PlayerWithin(f32),
+    PlayerBeyond(f32),
+    // fraction of max hp
+    HpBelow(f32),
+    HpAbove(f32),
+    // the player is pointing at this entity, within the given angle and range
+    AimedAt { angle: f32, range: f32 },
+    // this entity is within the given angle of directly behind the player
+    BehindPlayer(f32),
+    // seconds spent in the current state
+    TimeInState(f32),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transition {
+    // states this transition can be taken from. empty means any state.
+    #[serde(default)]
+    pub from: Vec<AIState>,
+    pub to: AIState,
+    pub when: Condition,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Behaviour {
+    pub initial: AIState,
+    // how far behind the player to sit while pursuing. 0 flies straight at the player.
+    pub follow_distance: f32,
+    // how close to the bounds to get before pulling away from them
+    pub bound_margin: f32,
+    pub fire_angle: f32,
+    pub fire_range: f32,
+    pub patrol_radius: f32,
+    // checked in order, the first matching transition is taken
+    pub transitions: Vec<Transition>,
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Resource, Clone)]
+pub struct AIBehaviours {
+    pub behaviours: HashMap<AIType, Behaviour>,
+}
+
+impl Default for AIBehaviours {
+    fn default() -> Self {
+        let mut behaviours = HashMap::default();
+        behaviours.insert(
+            AIType::Basic,
+            Behaviour {
+                initial: AIState::Patrol,
+                follow_distance: 0.0,
+                bound_margin: 50.0,
+                fire_angle: 0.5,
+                fire_range: 700.0,
+                patrol_radius: 150.0,
+                transitions: vec![
+                    Transition {
+                        from: vec![AIState::Patrol],
+                        to: AIState::Pursue,
+                        when: Condition::Any(vec![
+                            Condition::PlayerWithin(600.0),
+                            Condition::TimeInState(2.0),
+                        ]),
+                    },
+                    Transition {
+                        from: vec![AIState::Pursue],
+                        to: AIState::Attack,
+                        when: Condition::PlayerWithin(500.0),
+                    },
+                    Transition {
+                        from: vec![AIState::Attack],
+                        to: AIState::Pursue,
+                        when: Condition::PlayerBeyond(700.0),
+                    },
+                ],
+            },
+        );
+        behaviours.insert(
+            AIType::Ace,
+            Behaviour {
+                initial: AIState::Pursue,
+                follow_distance: 200.0,
+                bound_margin: 100.0,
+                fire_angle: 0.15,
+                fire_range: 600.0,
+                patrol_radius: 150.0,
+                transitions: vec![
+                    Transition {
+                        from: vec![AIState::Patrol, AIState::Pursue, AIState::Attack],
+                        to: AIState::Flee,
+                        when: Condition::HpBelow(0.3),
+                    },
+                    Transition {
+                        from: vec![AIState::Flee],
+                        to: AIState::Pursue,
+                        when: Condition::HpAbove(0.6),
+                    },
+                    Transition {
+                        from: vec![AIState::Patrol, AIState::Pursue, AIState::Attack],
+                        to: AIState::Evade,
+                        when: Condition::AimedAt {
+                            angle: 0.25,
+                            range: 500.0,
+                        },
+                    },
+                    Transition {
+                        from: vec![AIState::Evade],
+                        to: AIState::Pursue,
+                        when: Condition::TimeInState(0.8),
+                    },
+                    Transition {
+                        from: vec![AIState::Pursue],
+                        to: AIState::Attack,
+                        when: Condition::All(vec![
+                            Condition::BehindPlayer(0.8),
+                            Condition::PlayerWithin(350.0),
+                        ]),
+                    },
+                    Transition {
+                        from: vec![AIState::Attack],
+                        to: AIState::Pursue,
+                        when: Condition::PlayerBeyond(500.0),
+                    },
+                ],
+            },
+        );
+        behaviours.insert(
+            AIType::Lancer,
+            Behaviour {
+                initial: AIState::Pursue,
+                follow_distance: 0.0,
+                bound_margin: 80.0,
+                fire_angle: 0.1,
+                fire_range: 550.0,
+                patrol_radius: 150.0,
+                transitions: vec![
+                    Transition {
+                        from: vec![AIState::Pursue],
+                        to: AIState::Attack,
+                        when: Condition::PlayerWithin(450.0),
+                    },
+                    Transition {
+                        from: vec![AIState::Attack],
+                        to: AIState::Pursue,
+                        when: Condition::PlayerBeyond(650.0),
+                    },
+                ],
+            },
+        );
+        AIBehaviours { behaviours }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct AIStateMachine {
+    // none until the behaviour's initial state has been entered
+    state: Option<AIState>,
+    time_in_state: f32,
+    // where patrols are centered
+    anchor: Vec3,
+    evade_direction: f32,
+}
+
+impl AIStateMachine {
+    fn enter(&mut self, state: AIState) {
+        self.state = Some(state);
+        self.time_in_state = 0.0;
+    }
+}
+
+// everything conditions are evaluated against
+struct ConditionContext {
+    distance_to_player: f32,
+    hp_fraction: f32,
+    // player's facing direction, and the direction from the player to this entity
+    player_forward: Vec3,
+    player_to_self: Vec3,
+    time_in_state: f32,
+}
+
+impl Condition {
+    fn evaluate(&self, context: &ConditionContext) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::PlayerWithin(distance) => context.distance_to_player < *distance,
+            Condition::PlayerBeyond(distance) => context.distance_to_player > *distance,
+            Condition::HpBelow(fraction) => context.hp_fraction < *fraction,
+            Condition::HpAbove(fraction) => context.hp_fraction > *fraction,
+            Condition::AimedAt { angle, range } => {
+                context.distance_to_player < *range
+                    && context.player_forward.angle_between(context.player_to_self) < *angle
+            }
+            Condition::BehindPlayer(angle) => {
+                (-context.player_forward).angle_between(context.player_to_self) < *angle
+            }
+            Condition::TimeInState(seconds) => context.time_in_state > *seconds,
+            Condition::All(conditions) => conditions.iter().all(|c| c.evaluate(context)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.evaluate(context)),
+        }
+    }
+}
+
+pub fn ai_transition_system(
+    time: Res<Time>,
+    behaviours: Res<AIBehaviours>,
+    mut query: Query<(&AI, &mut AIStateMachine, &Transform, &HP), Without<Player>>,
+    player: Query<(&Transform, &TurnAngle), With<Player>>,
+) -> Result<(), BevyError> {
+    let (player_transform, player_turn_angle) = player.single()?;
+    let player_forward = Quat::from_rotation_z(**player_turn_angle) * Vec3::Y;
+
+    for (ai, mut machine, transform, hp) in query.iter_mut() {
+        let Some(behaviour) = behaviours.behaviours.get(&ai.ai_type) else {
+            continue;
+        };
+        let Some(state) = machine.state else {
+            machine.anchor = transform.translation;
+            machine.enter(behaviour.initial);
+            continue;
+        };
+        machine.time_in_state += time.delta_secs();
+
+        let player_to_self = transform.translation - player_transform.translation;
+        let context = ConditionContext {
+            distance_to_player: player_to_self.length(),
+            hp_fraction: hp.hp / hp.max,
+            player_forward,
+            player_to_self,
+            time_in_state: machine.time_in_state,
+        };
+
+        let next = behaviour.transitions.iter().find(|transition| {
+            transition.to != state
+                && (transition.from.is_empty() || transition.from.contains(&state))
+                && transition.when.evaluate(&context)
+        });
+        if let Some(transition) = next {
+            machine.enter(transition.to);
+            if transition.to == AIState::Evade {
+                // break towards whichever side is already closer to perpendicular to the player's aim
+                machine.evade_direction = if player_forward.cross(player_to_self).z >= 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+            }
+        }
+    }
+    Ok(())
+}
+
+// writes the intent for whatever state each entity is in
+pub fn ai_intent_system(
+    time: Res<Time>,
+    game_config: Res<GameConfig>,
+    behaviours: Res<AIBehaviours>,
+    mut query: Query<
+        (
+            &mut Intent,
+            &AI,
+            &AIStateMachine,
+            &Transform,
+            &TurnAngle,
+            &Physics,
+            &WeaponData,
+            &AimError,
+        ),
+        Without<Player>,
+    >,
+    player: Query<(&Transform, &Physics, &TurnAngle), With<Player>>,
+) -> Result<(), BevyError> {
+    let (player_transform, player_physics, player_turn_angle) = player.single()?;
+    let player_position = player_transform.translation;
+    let player_forward = Quat::from_rotation_z(**player_turn_angle) * Vec3::Y;
+
+    for (mut intent, ai, machine, transform, turn_angle, physics, weapon, aim_error) in
+        query.iter_mut()
+    {
+        let (Some(behaviour), Some(state)) =
+            (behaviours.behaviours.get(&ai.ai_type), machine.state)
+        else {
+            continue;
+        };
+
+        let position = transform.translation;
+        let forward = Quat::from_rotation_z(**turn_angle) * Vec3::Y;
+        let to_player = player_position - position;
+
+        intent.fire = false;
+        intent.brake = false;
+
+        // staying inside the bounds takes priority over everything else
+        if position.y > game_config.upper_bound - behaviour.bound_margin && forward.y > 0.0 {
+            intent.turn_intent = steer_towards(forward, Vec3::NEG_Y);
+            intent.accelerate = false;
+            continue;
+        }
+        if position.y < game_config.lower_bound + behaviour.bound_margin && forward.y < 0.0 {
+            intent.turn_intent = steer_towards(forward, Vec3::Y);
+            intent.accelerate = true;
+            continue;
+        }
+
+        match state {
+            AIState::Patrol => {
+                let to_anchor = machine.anchor - position;
+                let target = if to_anchor.length() > behaviour.patrol_radius {
+                    to_anchor
+                } else {
+                    // circle counterclockwise around the anchor
+                    Quat::from_rotation_z(-FRAC_PI_2) * to_anchor
+                };
+                intent.turn_intent = steer_towards(forward, target);
+                intent.accelerate = forward.angle_between(target) < 0.9;
+            }
+            AIState::Pursue => {
+                let target = if behaviour.follow_distance > 0.0 {
+                    player_position - player_forward * behaviour.follow_distance - position
+                } else {
+                    to_player
+                };
+                intent.turn_intent = steer_towards(forward, target);
+                intent.accelerate = true;
+            }
+            AIState::Attack => {
+                let (muzzle_speed, gravity) = projectile_parameters(weapon, time.delta_secs());
+                let solution = solve_intercept(
+                    position,
+                    physics.velocity,
+                    muzzle_speed,
+                    gravity,
+                    player_position,
+                    player_physics.velocity,
+                );
+                // without an intercept, close in on the player and hold fire
+                let aim = match &solution {
+                    Some(solution) => aim_error.apply(solution.direction),
+                    None => to_player,
+                };
+                let angle = forward.angle_between(aim);
+                intent.turn_intent = steer_towards(forward, aim);
+
+                let distance = to_player.length();
+                let closing_speed =
+                    (physics.velocity - player_physics.velocity).dot(to_player.normalize_or_zero());
+                // boost to catch up, brake if about to overshoot the follow distance
+                intent.accelerate = angle < 0.9 && distance > behaviour.follow_distance.max(10.0);
+                intent.brake = closing_speed > 0.0 && distance < behaviour.follow_distance * 0.5;
+                // only fire if the shot arrives before the projectile expires
+                intent.fire = solution
+                    .as_ref()
+                    .is_some_and(|solution| solution.time < weapon.lifetime.as_secs_f32())
+                    && angle < behaviour.fire_angle
+                    && distance < behaviour.fire_range;
+            }
+            AIState::Evade => {
+                let evade =
+                    Quat::from_rotation_z(machine.evade_direction * FRAC_PI_2) * player_forward;
+                intent.turn_intent = steer_towards(forward, evade);
+                intent.accelerate = true;
+            }
+            AIState::Flee => {
+                intent.turn_intent = steer_towards(forward, -to_player);
+                intent.accelerate = true;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{config::GameConfig, input::Intent, player::Player};
+
+use super::{AI, AIType};
+
+// how far below the water surface a submarine cruises while submerged
+pub const SUBMARINE_DEPTH: f32 = 150.0;
+// submarines try to stay under the player, but not directly under them.
+const SUBMARINE_FOLLOW_DISTANCE: f32 = 100.0;
+const SUBMERGED_TIME: Duration = Duration::from_millis(6000);
+const SURFACED_TIME: Duration = Duration::from_millis(2500);
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SubmarinePhase {
+    // hidden underwater, shadowing the player
+    Submerged,
+    // rising towards the surface
+    Surfacing,
+    // on the surface, launching missiles
+    Surfaced,
+}
+
+#[derive(Component)]
+pub struct SubmarineState {
+    pub phase: SubmarinePhase,
+    timer: Timer,
+}
+
+impl Default for SubmarineState {
+    fn default() -> Self {
+        SubmarineState {
+            phase: SubmarinePhase::Submerged,
+            timer: Timer::new(SUBMERGED_TIME, TimerMode::Once),
+        }
+    }
+}
+
+impl SubmarineState {
+    // the height the submarine is currently trying to reach
+    pub fn target_height(&self, game_config: &GameConfig) -> f32 {
+        match self.phase {
+            SubmarinePhase::Submerged => game_config.lower_bound - SUBMARINE_DEPTH,
+            SubmarinePhase::Surfacing | SubmarinePhase::Surfaced => game_config.lower_bound,
+        }
+    }
+}
+
+pub fn submarine_ai(
+    time: Res<Time>,
+    game_config: Res<GameConfig>,
+    mut query: Query<(&mut Intent, &mut SubmarineState, &Transform, &AI), Without<Player>>,
+    player: Query<&Transform, With<Player>>,
+) -> Result<(), BevyError> {
+    let player_position = player.single()?.translation;
+    for (mut intent, mut state, transform, ai) in query.iter_mut() {
+        if ai.ai_type != AIType::Submarine {
+            continue;
+        }
+
+        match state.phase {
+            SubmarinePhase::Submerged => {
+                if state.timer.tick(time.delta()).finished() {
+                    state.phase = SubmarinePhase::Surfacing;
+                }
+            }
+            SubmarinePhase::Surfacing => {
+                if transform.translation.y >= game_config.lower_bound {
+                    state.phase = SubmarinePhase::Surfaced;
+                    state.timer = Timer::new(SURFACED_TIME, TimerMode::Once);
+                }
+            }
+            SubmarinePhase::Surfaced => {
+                if state.timer.tick(time.delta()).finished() {
+                    // dive again
+                    state.phase = SubmarinePhase::Submerged;
+                    state.timer = Timer::new(SUBMERGED_TIME, TimerMode::Once);
+                }
+            }
+        }
+
+        let dx = player_position.x - transform.translation.x;
+        // like boats, turn_intent is the horizontal drive direction, positive is left.
+        intent.turn_intent = -dx.signum();
+        // submarines hold still while surfaced so they make a stable launch platform
+        intent.accelerate =
+            state.phase != SubmarinePhase::Surfaced && dx.abs() > SUBMARINE_FOLLOW_DISTANCE;
+        intent.brake = !intent.accelerate;
+        intent.fire = state.phase == SubmarinePhase::Surfaced;
+    }
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use bevy::{prelude::*, reflect::TypePath, sprite::Anchor};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::{camera_rig_system, camera_shake_system},
+    config::GameConfig,
+    misc::hitstun::in_game_or_hitstun,
+    reset::RunScoped,
+};
+
+// parallax background, loaded from background.ron.
+// each layer is one tiled sprite, a couple of tiles bigger than the view, that gets moved along with the camera
+// and snapped back by whole tiles so it looks like it goes on forever.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum LayerBound {
+    Upper,
+    Lower,
+}
+
+// which edge of a band sits at its height
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum BandAlign {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackgroundLayer {
+    // relative to the assets folder
+    pub image: String,
+    // layers are drawn in order of z. everything in the game is drawn at z 1 and up.
+    pub z: f32,
+    // how much the layer follows the camera, per axis. 0.0 is fixed to the world, 1.0 is fixed to the screen.
+    // distant layers are closer to 1.0.
+    pub parallax: (f32, f32),
+    // size of each tile, relative to the image
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    // repeats vertically too, filling the whole view. otherwise the layer is a single horizontal band.
+    #[serde(default)]
+    pub repeat_y: bool,
+    // height of a band, in world units, added to the bound it's relative to if any
+    #[serde(default)]
+    pub y: f32,
+    #[serde(default)]
+    pub relative_to: Option<LayerBound>,
+    #[serde(default)]
+    pub align: BandAlign,
+    // srgba tint
+    #[serde(default = "default_color")]
+    pub color: (f32, f32, f32, f32),
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_color() -> (f32, f32, f32, f32) {
+    (1.0, 1.0, 1.0, 1.0)
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Resource, Clone)]
+pub struct BackgroundLayers {
+    pub layers: Vec<BackgroundLayer>,
+}
+
+impl Default for BackgroundLayers {
+    fn default() -> Self {
+        let layer = |image: &str, z, parallax| BackgroundLayer {
+            image: image.to_string(),
+            z,
+            parallax,
+            scale: 1.0,
+            repeat_y: false,
+            y: 0.0,
+            relative_to: None,
+            align: BandAlign::Center,
+            color: default_color(),
+        };
+        BackgroundLayers {
+            layers: vec![
+                BackgroundLayer {
+                    repeat_y: true,
+                    ..layer("images/sky.png", -10.0, (1.0, 1.0))
+                },
+                BackgroundLayer {
+                    y: 250.0,
+                    ..layer("images/clouds_far.png", -9.0, (0.8, 0.8))
+                },
+                BackgroundLayer {
+                    scale: 1.5,
+                    y: 50.0,
+                    ..layer("images/clouds_near.png", -8.0, (0.5, 0.5))
+                },
+                BackgroundLayer {
+                    repeat_y: true,
+                    relative_to: Some(LayerBound::Lower),
+                    align: BandAlign::Top,
+                    ..layer("images/water.png", -1.0, (0.0, 0.0))
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ParallaxLayer(BackgroundLayer);
+
+pub fn setup_background(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    background: Res<BackgroundLayers>,
+) {
+    for layer in background.layers.iter() {
+        let (r, g, b, a) = layer.color;
+        commands.spawn((
+            RunScoped,
+            ParallaxLayer(layer.clone()),
+            Sprite {
+                image: asset_server.load(&layer.image),
+                color: Color::srgba(r, g, b, a),
+                image_mode: SpriteImageMode::Tiled {
+                    tile_x: true,
+                    tile_y: layer.repeat_y,
+                    stretch_value: layer.scale,
+                },
+                anchor: match layer.align {
+                    BandAlign::Top => Anchor::TopCenter,
+                    BandAlign::Center => Anchor::Center,
+                    BandAlign::Bottom => Anchor::BottomCenter,
+                },
+                ..Default::default()
+            },
+            Transform::from_translation(Vec3::new(0.0, 0.0, layer.z)),
+            // hidden until the image has loaded and the layer has been sized
+            Visibility::Hidden,
+        ));
+    }
+}
+
+// the smallest even number of tiles that covers `view` with a tile to spare on each side.
+// even, so that tile edges land in the same place however the sprite's tiling is aligned.
+fn tiles_to_cover(view: f32, tile: f32) -> f32 {
+    2.0 * ((view / tile + 2.0) / 2.0).ceil()
+}
+
+// where to put the layer so it covers the camera, given where its tiles line up
+fn snap_to_tiles(camera: f32, origin: f32, tile: f32) -> f32 {
+    origin + ((camera - origin) / tile).round() * tile
+}
+
+pub fn parallax_system(
+    game_config: Res<GameConfig>,
+    images: Res<Assets<Image>>,
+    camera: Query<(&Transform, &Projection, &Camera), Without<ParallaxLayer>>,
+    mut layers: Query<(&ParallaxLayer, &mut Sprite, &mut Transform, &mut Visibility)>,
+) {
+    let Ok((cam_transform, projection, camera)) = camera.single() else {
+        return;
+    };
+    let scale = match projection {
+        Projection::Orthographic(projection) => projection.scale,
+        _ => 1.0,
+    };
+    let view = camera
+        .logical_viewport_size()
+        .unwrap_or(Vec2::new(1280.0, 720.0))
+        * scale;
+    let cam = cam_transform.translation.truncate();
+
+    for (ParallaxLayer(layer), mut sprite, mut transform, mut visibility) in layers.iter_mut() {
+        let Some(image) = images.get(&sprite.image) else {
+            continue;
+        };
+        let tile = image.size_f32() * layer.scale;
+        // the layer drifts along with the camera by its parallax factor
+        let origin = cam * Vec2::new(layer.parallax.0, layer.parallax.1);
+
+        let x = snap_to_tiles(cam.x, origin.x, tile.x);
+        let width = tiles_to_cover(view.x, tile.x) * tile.x;
+        let (y, height) = if layer.repeat_y && layer.relative_to.is_none() {
+            (
+                snap_to_tiles(cam.y, origin.y, tile.y),
+                tiles_to_cover(view.y, tile.y) * tile.y,
+            )
+        } else {
+            let bound = match layer.relative_to {
+                Some(LayerBound::Upper) => game_config.upper_bound,
+                Some(LayerBound::Lower) => game_config.lower_bound,
+                None => 0.0,
+            };
+            let y = bound + layer.y + origin.y;
+            // a band relative to a bound can still repeat, away from its aligned edge, far enough to fill the view
+            let height = if layer.repeat_y {
+                tiles_to_cover(view.y + (cam.y - y).abs() * 2.0, tile.y) * tile.y
+            } else {
+                tile.y
+            };
+            (y, height)
+        };
+
+        sprite.custom_size = Some(Vec2::new(width, height));
+        transform.translation.x = x;
+        transform.translation.y = y;
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BackgroundLayers>().add_systems(
+            PostUpdate,
+            parallax_system
+                .after(camera_rig_system)
+                .before(camera_shake_system)
+                .before(TransformSystem::TransformPropagate)
+                // layers only exist during a run
+                .run_if(in_game_or_hitstun),
+        );
+    }
+}
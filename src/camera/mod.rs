@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+// use bevy::prelude::Camera2dBundle;
+// use bevy::core_pipeline::prelude::Camera2dBundle;
+
+mod rig;
+mod shake;
+
+pub use rig::{CameraRig, camera_rig_system};
+pub use shake::{CameraShake, camera_shake_system};
+
+use shake::camera_trauma_system;
+
+use crate::{
+    config::GameConfig,
+    enemy::Enemy,
+    gamestate::GameState,
+    misc::{hitstun::in_game_or_hitstun, in_game_no_hitstun},
+    physics::Physics,
+    player::Player,
+    reset::{RunResetAppExt, RunTeardown},
+};
+
+// the rig on this camera is pointed at the player whenever there is one
+#[derive(Component)]
+pub struct FollowPlayer;
+
+pub fn camera_startup_system(mut commands: Commands, query: Query<Entity, With<Camera>>) {
+    if let Ok(camera) = query.single() {
+        commands
+            .entity(camera)
+            .insert_if_new((CameraRig::default(), FollowPlayer));
+    } else if query.is_empty() {
+        commands.spawn((Camera2d, CameraRig::default(), FollowPlayer));
+    }
+}
+
+// how much of the world can be seen past the vertical bounds
+const BOUNDS_MARGIN: f32 = 300.0;
+
+// projection scale when standing still with nothing around
+const MIN_ZOOM: f32 = 1.0;
+// extra zoom out at full speed. speed is eased in the same way as the look-ahead.
+const SPEED_ZOOM: f32 = 0.3;
+// enemies closer than this pull the camera out to keep them on screen
+const THREAT_RANGE: f32 = 900.0;
+// screen space kept between the nearest threat and the edge of the view, in logical pixels
+const THREAT_MARGIN: f32 = 80.0;
+// the space between the vertical bounds never takes up less than this fraction of the view's height
+const MIN_BOUNDS_FRACTION: f32 = 0.6;
+// how quickly the zoom catches up to its target, per second
+const ZOOM_SMOOTHING: f32 = 3.0;
+
+pub fn camera_follow_player_system(
+    game_config: Res<GameConfig>,
+    player: Query<Entity, With<Player>>,
+    mut cameras: Query<&mut CameraRig, With<FollowPlayer>>,
+) {
+    let Ok(player) = player.single() else {
+        return;
+    };
+    for mut rig in cameras.iter_mut() {
+        rig.set_target(player);
+        // the playfield is only bounded vertically
+        rig.bounds = Some(Rect::new(
+            f32::NEG_INFINITY,
+            game_config.lower_bound - BOUNDS_MARGIN,
+            f32::INFINITY,
+            game_config.upper_bound + BOUNDS_MARGIN,
+        ));
+    }
+}
+
+// zoom out when the rig's target goes fast, and when enemies get close enough to it that they'd otherwise be off screen
+pub fn camera_zoom_system(
+    time: Res<Time>,
+    game_config: Res<GameConfig>,
+    mut cameras: Query<(&CameraRig, &mut Projection, &Camera)>,
+    targets: Query<(&GlobalTransform, Option<&Physics>)>,
+    enemies: Query<&GlobalTransform, With<Enemy>>,
+) {
+    for (rig, mut projection, camera) in cameras.iter_mut() {
+        let Projection::Orthographic(projection) = projection.as_mut() else {
+            continue;
+        };
+        let Some((target_transform, target_physics)) =
+            rig.target().and_then(|target| targets.get(target).ok())
+        else {
+            continue;
+        };
+        let target_translation = target_transform.translation().truncate();
+        let velocity_len = target_physics
+            .map(|physics| physics.velocity.length())
+            .unwrap_or(0.0);
+        let speed = 1.0 - (-velocity_len / rig.look_ahead_speed).exp();
+
+        let half_view = camera
+            .logical_viewport_size()
+            .unwrap_or(Vec2::new(1280.0, 720.0))
+            / 2.0;
+        let mut target_scale = MIN_ZOOM + SPEED_ZOOM * speed;
+        let nearest_threat = enemies
+            .iter()
+            .map(|transform| transform.translation().truncate() - target_translation)
+            .filter(|offset| offset.length() < THREAT_RANGE)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+        if let Some(offset) = nearest_threat {
+            // the scale at which the threat sits THREAT_MARGIN inside the edge of the view
+            let fit = (offset.abs() / (half_view - THREAT_MARGIN).max(Vec2::ONE)).max_element();
+            target_scale = target_scale.max(fit);
+        }
+        // don't zoom out so far that the bounds get too small to read
+        let max_scale = (game_config.upper_bound - game_config.lower_bound)
+            / (2.0 * half_view.y * MIN_BOUNDS_FRACTION);
+        let target_scale = target_scale.clamp(MIN_ZOOM, max_scale.max(MIN_ZOOM));
+        projection.scale = projection.scale.lerp(
+            target_scale,
+            1.0 - (-ZOOM_SMOOTHING * time.delta_secs()).exp(),
+        );
+    }
+}
+
+// the camera keeps whatever tilt and zoom it had when the run ended, so put it back
+pub fn camera_teardown_system(mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>) {
+    for (mut transform, mut projection) in camera.iter_mut() {
+        transform.rotation = Quat::IDENTITY;
+        if let Projection::Orthographic(projection) = projection.as_mut() {
+            projection.scale = MIN_ZOOM;
+        }
+    }
+}
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraShake>()
+            .reset_on_run_teardown::<CameraShake>()
+            .add_systems(
+                OnTransition {
+                    exited: GameState::MainMenu,
+                    entered: GameState::InGame,
+                },
+                camera_startup_system,
+            )
+            .add_systems(Update, camera_trauma_system.run_if(in_game_or_hitstun))
+            .add_systems(
+                PostUpdate,
+                (
+                    (camera_follow_player_system, camera_zoom_system)
+                        .chain()
+                        .run_if(in_game_no_hitstun),
+                    // the shake goes on top of wherever the rig puts the camera, so both keep running through hitstun,
+                    // which is when the shake from the hit that caused it matters most
+                    (camera_rig_system, camera_shake_system)
+                        .chain()
+                        .run_if(in_game_or_hitstun),
+                )
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(RunTeardown, camera_teardown_system);
+    }
+}
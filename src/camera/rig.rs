@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use crate::physics::Physics;
+
+// follows a target entity. the camera is pulled towards the target plus some look-ahead by a critically damped spring,
+// and doesn't move at all while the target stays within the deadzone.
+// distances are in screen space (logical pixels at zoom 1), so the feel doesn't change with zoom.
+#[derive(Component)]
+pub struct CameraRig {
+    target: Option<Entity>,
+    // how quickly the spring closes the gap, in radians per second. higher is snappier.
+    pub stiffness: f32,
+    // how far ahead of the target to look at full speed
+    pub look_ahead: f32,
+    // speed at which the look-ahead is about two thirds of the way to its maximum
+    pub look_ahead_speed: f32,
+    // how far ahead of the target to look in the direction it's facing
+    pub aim_look_ahead: f32,
+    // half size of the box the focus point can move around in before the camera follows
+    pub deadzone: Vec2,
+    // world space area the view is kept inside of. use infinite extents to leave an axis unclamped.
+    pub bounds: Option<Rect>,
+    // where the camera is heading, moved only as far as needed to keep the focus point inside the deadzone
+    anchor: Vec2,
+    // spring state
+    position: Vec2,
+    velocity: Vec2,
+    // jump straight to the target on the next update, instead of swinging over from wherever the camera was
+    snap: bool,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        CameraRig {
+            target: None,
+            stiffness: 8.0,
+            look_ahead: 100.0,
+            look_ahead_speed: 1000.0,
+            aim_look_ahead: 10.0,
+            deadzone: Vec2::splat(20.0),
+            bounds: None,
+            anchor: Vec2::ZERO,
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            snap: true,
+        }
+    }
+}
+
+impl CameraRig {
+    pub fn target(&self) -> Option<Entity> {
+        self.target
+    }
+    pub fn set_target(&mut self, target: Entity) {
+        if self.target != Some(target) {
+            self.target = Some(target);
+            self.snap = true;
+        }
+    }
+
+    // critically damped spring step, stable for any delta time.
+    // see "critically damped ease-in/ease-out smoothing", game programming gems 4.
+    fn step_spring(&mut self, goal: Vec2, dt: f32) {
+        let x = self.stiffness * dt;
+        let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+        let change = self.position - goal;
+        let temp = (self.velocity + change * self.stiffness) * dt;
+        self.velocity = (self.velocity - temp * self.stiffness) * decay;
+        self.position = goal + (change + temp) * decay;
+    }
+}
+
+// keeps the view's center far enough inside the bounds that the view doesn't leave them.
+// if the view is bigger than the bounds, it's centered on them instead.
+fn clamp_to_bounds(center: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let (min, max) = (bounds.min + half_view, bounds.max - half_view);
+    let clamp = |value: f32, min: f32, max: f32, mid: f32| {
+        if min > max {
+            mid
+        } else {
+            value.clamp(min, max)
+        }
+    };
+    let mid = bounds.center();
+    Vec2::new(
+        clamp(center.x, min.x, max.x, mid.x),
+        clamp(center.y, min.y, max.y, mid.y),
+    )
+}
+
+pub fn camera_rig_system(
+    time: Res<Time>,
+    mut cameras: Query<(&mut CameraRig, &mut Transform, &Projection, &Camera)>,
+    targets: Query<(&GlobalTransform, Option<&Physics>), Without<CameraRig>>,
+) {
+    for (mut rig, mut cam_transform, projection, camera) in cameras.iter_mut() {
+        let Some((target_transform, target_physics)) =
+            rig.target.and_then(|target| targets.get(target).ok())
+        else {
+            continue;
+        };
+        let scale = match projection {
+            Projection::Orthographic(projection) => projection.scale,
+            _ => 1.0,
+        };
+        let (_, target_rotation, target_translation) =
+            target_transform.to_scale_rotation_translation();
+        let velocity = target_physics
+            .map(|physics| physics.velocity.truncate())
+            .unwrap_or(Vec2::ZERO);
+
+        // push the camera in the direction of travel, easing in with speed so it never gets too far ahead,
+        // and slightly in the direction the target is facing
+        let speed = 1.0 - (-velocity.length() / rig.look_ahead_speed).exp();
+        let speed_component = velocity.normalize_or_zero() * rig.look_ahead * speed;
+        let aim_component = (target_rotation * Vec3::Y).truncate() * rig.aim_look_ahead;
+        let focus = target_translation.truncate() + (speed_component + aim_component) * scale;
+
+        if rig.snap {
+            rig.snap = false;
+            rig.anchor = focus;
+            rig.position = focus;
+            rig.velocity = Vec2::ZERO;
+        }
+        let deadzone = rig.deadzone * scale;
+        rig.anchor = rig.anchor.clamp(focus - deadzone, focus + deadzone);
+        let mut goal = rig.anchor;
+        if let Some(bounds) = rig.bounds {
+            let half_view = camera
+                .logical_viewport_size()
+                .unwrap_or(Vec2::new(1280.0, 720.0))
+                / 2.0
+                * scale;
+            goal = clamp_to_bounds(goal, half_view, bounds);
+        }
+        rig.step_spring(goal, time.delta_secs());
+
+        let cam_z = cam_transform.translation.z;
+        cam_transform.translation = rig.position.extend(cam_z);
+        cam_transform.rotation = Quat::IDENTITY;
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+use crate::{
+    events::{DamageType, EnemyDeath, EnemyHit, PlayerHit},
+    reset::RunReset,
+    userdata::UserData,
+};
+
+// trauma based screen shake. events add trauma, which decays over time,
+// and the camera is offset and rotated by noise scaled by trauma squared, so small hits barely register and big ones kick hard.
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    trauma: f32,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+}
+
+impl RunReset for CameraShake {
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// trauma lost per second
+const TRAUMA_DECAY: f32 = 1.2;
+// trauma added per point of damage the player takes, and the most a single hit can add
+const TRAUMA_PER_PLAYER_DAMAGE: f32 = 0.02;
+const MAX_PLAYER_HIT_TRAUMA: f32 = 0.6;
+const TRAUMA_ENEMY_DEATH: f32 = 0.15;
+// extra trauma for explosive hits, on the player or on enemies
+const TRAUMA_EXPLOSION: f32 = 0.25;
+// shake at full trauma
+const MAX_SHAKE_OFFSET: f32 = 30.0;
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+// how fast the noise is scrolled through, higher is more jittery
+const SHAKE_FREQUENCY: f32 = 15.0;
+
+// hashes a lattice point to a gradient in [-1, 1]
+fn gradient(i: i32, seed: u32) -> f32 {
+    let mut h = (i as u32).wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+// 1d perlin noise, roughly in [-1, 1]. different seeds give independent channels.
+fn perlin(x: f32, seed: u32) -> f32 {
+    let i = x.floor();
+    let t = x - i;
+    let (g0, g1) = (gradient(i as i32, seed), gradient(i as i32 + 1, seed));
+    let fade = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    // the raw noise peaks at 0.5
+    2.0 * (g0 * t).lerp(g1 * (t - 1.0), fade)
+}
+
+pub fn camera_trauma_system(
+    time: Res<Time>,
+    mut shake: ResMut<CameraShake>,
+    mut player_hits: EventReader<PlayerHit>,
+    mut enemy_deaths: EventReader<EnemyDeath>,
+    mut enemy_hits: EventReader<EnemyHit>,
+) {
+    // beams hit every tick for a little damage, so they shake the camera steadily while held on the player
+    for event in player_hits.read() {
+        shake.add_trauma((event.damage * TRAUMA_PER_PLAYER_DAMAGE).min(MAX_PLAYER_HIT_TRAUMA));
+        // i.e. submarine missiles
+        if event.source.damage_type == DamageType::Explosive {
+            shake.add_trauma(TRAUMA_EXPLOSION);
+        }
+    }
+    for _ in enemy_deaths.read() {
+        shake.add_trauma(TRAUMA_ENEMY_DEATH);
+    }
+    for event in enemy_hits.read() {
+        if event.source.damage_type == DamageType::Explosive {
+            shake.add_trauma(TRAUMA_EXPLOSION);
+        }
+    }
+    shake.add_trauma(-TRAUMA_DECAY * time.delta_secs());
+}
+
+// goes on top of wherever the rig put the camera this frame
+pub fn camera_shake_system(
+    time: Res<Time>,
+    shake: Res<CameraShake>,
+    userdata: Res<UserData>,
+    mut camera: Query<(&mut Transform, &Projection), With<Camera>>,
+) {
+    let amount = shake.trauma().powi(2) * userdata.screen_shake;
+    let t = time.elapsed_secs() * SHAKE_FREQUENCY;
+    for (mut transform, projection) in camera.iter_mut() {
+        // in screen space, so it looks the same at any zoom
+        let scale = match projection {
+            Projection::Orthographic(projection) => projection.scale,
+            _ => 1.0,
+        };
+        let offset = Vec2::new(perlin(t, 0), perlin(t, 1)) * MAX_SHAKE_OFFSET * amount * scale;
+        transform.translation += offset.extend(0.0);
+        transform.rotation = Quat::from_rotation_z(perlin(t, 2) * MAX_SHAKE_ANGLE * amount);
+    }
+}
@@ -16,6 +16,73 @@ pub struct GameConfig {
     pub superboost_acceleration_modifier: f32,
     pub superboost_turn_speed_modifier: f32,
     pub hitstun_time_ms: u32,
+    // seconds before the first wave of a run
+    #[serde(default = "default_first_wave_delay")]
+    pub first_wave_delay: f32,
+    // heat lost per second while there's no combo going
+    #[serde(default = "default_heat_decay_rate")]
+    pub heat_decay_rate: f32,
+    // extra heat gained from a kill, as a fraction of the enemy's heat, per point of combo
+    #[serde(default = "default_heat_combo_bonus")]
+    pub heat_combo_bonus: f32,
+    // sorted by threshold, lowest first
+    #[serde(default = "default_heat_tiers")]
+    pub heat_tiers: Vec<HeatTier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HeatTier {
+    pub name: String,
+    // heat at which this tier begins
+    pub threshold: f32,
+    // multiplies how often waves come
+    pub spawn_rate: f32,
+    // multiplies the damage of enemy weapons
+    pub damage_multiplier: f32,
+    // looping music track to switch to when entering this tier, relative to the assets folder
+    #[serde(default)]
+    pub music: Option<String>,
+}
+
+impl GameConfig {
+    // the tier for a given heat, and its index. none if there are no tiers configured.
+    pub fn heat_tier(&self, heat: f32) -> Option<(usize, &HeatTier)> {
+        self.heat_tiers
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, tier)| heat >= tier.threshold)
+            .or_else(|| self.heat_tiers.first().map(|tier| (0, tier)))
+    }
+}
+
+fn default_first_wave_delay() -> f32 {
+    5.0
+}
+
+fn default_heat_decay_rate() -> f32 {
+    0.02
+}
+
+fn default_heat_combo_bonus() -> f32 {
+    0.1
+}
+
+fn default_heat_tiers() -> Vec<HeatTier> {
+    let tier = |name: &str, threshold, spawn_rate, damage_multiplier| HeatTier {
+        name: name.to_string(),
+        threshold,
+        spawn_rate,
+        damage_multiplier,
+        music: None,
+    };
+    vec![
+        tier("Calm", 1.0, 1.0, 1.0),
+        tier("Warm", 2.0, 1.25, 1.1),
+        tier("Hot", 4.0, 1.5, 1.25),
+        tier("Blazing", 7.0, 2.0, 1.5),
+        tier("Inferno", 11.0, 2.5, 2.0),
+    ]
 }
 
 impl Default for GameConfig {
@@ -31,6 +98,10 @@ impl Default for GameConfig {
             superboost_acceleration_modifier: 2.0,
             superboost_turn_speed_modifier: 0.3333,
             hitstun_time_ms: 10,
+            first_wave_delay: default_first_wave_delay(),
+            heat_decay_rate: default_heat_decay_rate(),
+            heat_combo_bonus: default_heat_combo_bonus(),
+            heat_tiers: default_heat_tiers(),
         }
     }
 }
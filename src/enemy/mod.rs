@@ -1,40 +1,89 @@
-use std::time::Duration;
+use std::{f32::consts::TAU, time::Duration};
 
-use bevy::prelude::*;
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::random;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    ai::{AI, AIType, basic::plane_ai},
-    body_type_stats::PlaneMovementStats,
-    events::EnemyDeath,
+    ai::{
+        AI, AIType,
+        aim::{AimError, aim_error_system},
+        boat::{boat_ai, turret_aim_system},
+        state_machine::{AIBehaviours, AIStateMachine, ai_intent_system, ai_transition_system},
+        submarine::{SUBMARINE_DEPTH, SubmarineState, submarine_ai},
+    },
+    body_type_stats::{BoatStats, PlaneMovementStats},
+    config::GameConfig,
+    events::{DamageSource, EnemyDeath, EnemyHit},
     gamestate::GameState,
     input::Intent,
-    misc::{CollisionRadius, HP, ToVec3, VerticallyBounded, random_in_circle},
-    mods::guns::{WeaponData, WeaponSubtype, WeaponType},
+    misc::{
+        CollisionRadius, HP, VerticallyBounded,
+        contact_damage::{DealsContactDamage, TakesContactDamage},
+        in_game_no_hitstun,
+        water::Submersible,
+    },
+    mods::guns::{BeamCharge, Interceptable, WeaponData, WeaponSubtype, WeaponType},
     physics::Physics,
+    player::TurnAngle,
+    reset::RunScoped,
 };
 
 pub mod basic;
 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum EnemyType {
+    Basic,
+    Boat,
+    Destroyer,
+    DestroyerTurret,
+    Ace,
+    Submarine,
+    Lancer,
+}
+
+// where an enemy type is allowed to be spawned
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SpawnArea {
+    // anywhere between the bounds
+    Air,
+    // on the water surface, at the lower bound
+    Surface,
+    // below the water surface
+    Underwater,
+}
+
+impl EnemyType {
+    pub fn spawn_area(self) -> SpawnArea {
+        match self {
+            EnemyType::Basic | EnemyType::Ace | EnemyType::Lancer => SpawnArea::Air,
+            EnemyType::Boat | EnemyType::Destroyer | EnemyType::DestroyerTurret => {
+                SpawnArea::Surface
+            }
+            EnemyType::Submarine => SpawnArea::Underwater,
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Enemy {
+    pub enemy_type: EnemyType,
     pub score: usize,
     // pub xp: f32,
     pub heat: f32, // heat contribution from this enemy
 }
 
-pub fn add_basic_enemy(
-    commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    player_position: Vec3,
-) {
-    let basic_enemy_spawn_radius = 300.0;
-    let position = random_in_circle().to_vec3() * basic_enemy_spawn_radius + player_position;
-
+pub fn add_basic_enemy(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Vec3) {
     commands
         .spawn((
             Visibility::Visible,
             Transform::from_translation(position),
+            RunScoped,
             AI::new(AIType::Basic),
+            AIStateMachine::default(),
+            AimError::default(),
+            // planes share the player's movement pipeline, which steers using the turn angle
+            TurnAngle(random::<f32>() * TAU),
             Intent::default(),
             HP {
                 hp: 100.0,
@@ -46,6 +95,7 @@ pub fn add_basic_enemy(
                 turn_speed: 1.5,
             },
             Enemy {
+                enemy_type: EnemyType::Basic,
                 score: 16,
                 // xp_reward: 0.0,
                 heat: 0.5,
@@ -55,6 +105,7 @@ pub fn add_basic_enemy(
                 velocity: Vec3::new(0.0, 0.0, 0.0),
                 gravity: Vec3::new(0.0, -4.0, 0.0),
                 friction: 0.995,
+                ..Default::default()
             },
             VerticallyBounded {},
             WeaponData {
@@ -69,11 +120,20 @@ pub fn add_basic_enemy(
                     num_spawned_per_shot: 1,
                 },
                 lifetime: Duration::from_millis(3000),
+                // slow enough to be shot down
+                interceptable: Some(Interceptable::new(1.0, 2)),
                 ..WeaponType::MachineGun.data_from_type_and_handle(
                     asset_server.get_handle("images/bullet.png").unwrap(),
                 )
             },
-            CollisionRadius(10.0),
+            // planes ram into the player, and take damage from it as well
+            (
+                CollisionRadius(10.0),
+                TakesContactDamage {},
+                DealsContactDamage {
+                    damage_per_second: 40.0,
+                },
+            ),
         ))
         .with_children(|e| {
             // add sprite as child so that it's affected by the transform of the parent
@@ -95,20 +155,495 @@ pub fn add_basic_enemy(
         });
 }
 
+// rare, skilled enemy that hunts the player down. uses the same plane as basic enemies, but with better stats.
+pub fn add_ace_enemy(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Vec3) {
+    commands
+        .spawn((
+            Visibility::Visible,
+            Transform::from_translation(position),
+            RunScoped,
+            AI::new(AIType::Ace),
+            AIStateMachine::default(),
+            AimError::default(),
+            TurnAngle(random::<f32>() * TAU),
+            Intent::default(),
+            HP {
+                hp: 250.0,
+                max: 250.0,
+                regen: 5.0,
+            },
+            PlaneMovementStats {
+                acceleration: 9.0,
+                turn_speed: 3.0,
+            },
+            Enemy {
+                enemy_type: EnemyType::Ace,
+                score: 120,
+                heat: 2.0,
+            },
+            Physics {
+                mass: 50.0,
+                velocity: Vec3::new(0.0, 0.0, 0.0),
+                gravity: Vec3::new(0.0, -4.0, 0.0),
+                friction: 0.995,
+                ..Default::default()
+            },
+            VerticallyBounded {},
+            WeaponData {
+                timer: Timer::new(Duration::from_millis(250), TimerMode::Repeating),
+                damage: 15.0,
+                spread: 0.05,
+                subtype: WeaponSubtype::BulletBased {
+                    velocity: Vec3::new(0.0, 600.0, 0.0),
+                    gravity: Vec3::new(0.0, -0.3, 0.0),
+                    bullet_mass: 0.01,
+                    friction: 1.0,
+                    bullet_scale: 0.8,
+                    num_spawned_per_shot: 1,
+                },
+                lifetime: Duration::from_millis(1500),
+                ..WeaponType::MachineGun.data_from_type_and_handle(
+                    asset_server.get_handle("images/bullet.png").unwrap(),
+                )
+            },
+            // planes ram into the player, and take damage from it as well
+            (
+                CollisionRadius(10.0),
+                TakesContactDamage {},
+                DealsContactDamage {
+                    damage_per_second: 40.0,
+                },
+            ),
+        ))
+        .with_children(|e| {
+            e.spawn((
+                Sprite {
+                    image: asset_server
+                        .get_handle("images/enemy/basic_enemy.png")
+                        .unwrap(),
+                    // aces are painted red
+                    color: Color::srgb(1.0, 0.3, 0.3),
+                    ..Default::default()
+                },
+                Transform {
+                    scale: Vec3::splat(0.45),
+                    translation: Vec3::new(0.0, 0.0, 1.0),
+                    ..Default::default()
+                },
+                Visibility::Visible,
+            ));
+        });
+}
+
+// plane with a laser. it telegraphs each beam with a warning line before firing.
+pub fn add_lancer_enemy(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Vec3) {
+    commands
+        .spawn((
+            Visibility::Visible,
+            Transform::from_translation(position),
+            RunScoped,
+            AI::new(AIType::Lancer),
+            AIStateMachine::default(),
+            AimError::default(),
+            TurnAngle(random::<f32>() * TAU),
+            Intent::default(),
+            HP {
+                hp: 150.0,
+                max: 150.0,
+                regen: 0.0,
+            },
+            PlaneMovementStats {
+                acceleration: 5.0,
+                turn_speed: 1.0,
+            },
+            Enemy {
+                enemy_type: EnemyType::Lancer,
+                score: 60,
+                heat: 1.5,
+            },
+            Physics {
+                mass: 50.0,
+                velocity: Vec3::new(0.0, 0.0, 0.0),
+                gravity: Vec3::new(0.0, -4.0, 0.0),
+                friction: 0.995,
+                ..Default::default()
+            },
+            VerticallyBounded {},
+            // grouped, since the bundle is over the tuple size limit otherwise
+            (
+                WeaponData {
+                    damage: 80.0, // per second
+                    piercing: 0,
+                    subtype: WeaponSubtype::Laser {
+                        width: 6.0,
+                        max_dist: 600.0,
+                    },
+                    ..WeaponType::Laser.data_from_type_and_handle(
+                        asset_server.get_handle("images/bullet.png").unwrap(),
+                    )
+                },
+                BeamCharge::new(
+                    Duration::from_millis(1000),
+                    Duration::from_millis(1500),
+                    Duration::from_millis(2500),
+                ),
+            ),
+            // planes ram into the player, and take damage from it as well
+            (
+                CollisionRadius(12.0),
+                TakesContactDamage {},
+                DealsContactDamage {
+                    damage_per_second: 40.0,
+                },
+            ),
+        ))
+        .with_children(|e| {
+            e.spawn((
+                Sprite {
+                    image: asset_server
+                        .get_handle("images/enemy/basic_enemy.png")
+                        .unwrap(),
+                    // lancers are painted purple
+                    color: Color::srgb(0.7, 0.4, 1.0),
+                    ..Default::default()
+                },
+                Transform {
+                    scale: Vec3::splat(0.5),
+                    translation: Vec3::new(0.0, 0.0, 1.0),
+                    ..Default::default()
+                },
+                Visibility::Visible,
+            ));
+        });
+}
+
+// spawns an enemy of the given type at the given position.
+// enemies that are restricted to the water adjust the position's height themselves.
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    game_config: &GameConfig,
+    enemy_type: EnemyType,
+    position: Vec3,
+) {
+    match enemy_type {
+        EnemyType::Basic => add_basic_enemy(commands, asset_server, position),
+        EnemyType::Ace => add_ace_enemy(commands, asset_server, position),
+        EnemyType::Lancer => add_lancer_enemy(commands, asset_server, position),
+        EnemyType::Boat => add_boat_enemy(commands, asset_server, position, game_config),
+        EnemyType::Destroyer => add_destroyer_enemy(commands, asset_server, position, game_config),
+        EnemyType::Submarine => add_submarine_enemy(commands, asset_server, position, game_config),
+        EnemyType::DestroyerTurret => {
+            warn!("destroyer turrets can't be spawned on their own");
+        }
+    }
+}
+
+// an enemy that's a destructible part of a larger enemy.
+// destroying it disables it rather than despawning it, and the parent is unaffected.
+#[derive(Component)]
+pub struct EnemyPart;
+
+// independently aiming weapon mount, spawned as a child of an enemy.
+// aims using the parent's BoatStats::aim_speed.
+#[derive(Component, Default)]
+pub struct Turret {
+    pub angle: f32,
+}
+
+pub fn add_boat_enemy(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    game_config: &GameConfig,
+) {
+    // boats can only spawn on the water surface
+    let position = Vec3::new(position.x, game_config.lower_bound, 0.0);
+
+    commands
+        .spawn((
+            Visibility::Visible,
+            Transform::from_translation(position),
+            RunScoped,
+            AI::new(AIType::Boat),
+            Intent::default(),
+            HP {
+                hp: 150.0,
+                max: 150.0,
+                regen: 0.0,
+            },
+            BoatStats {
+                aim_speed: 1.5,
+                acceleration: 3.0,
+                friction: 0.98,
+            },
+            Enemy {
+                enemy_type: EnemyType::Boat,
+                score: 24,
+                heat: 0.75,
+            },
+            Physics {
+                mass: 500.0,
+                velocity: Vec3::ZERO,
+                gravity: Vec3::ZERO,
+                friction: 1.0,
+                ..Default::default()
+            },
+            CollisionRadius(20.0),
+        ))
+        .with_children(|e| {
+            // hull
+            e.spawn((
+                Sprite::from_color(Color::srgb(0.3, 0.3, 0.35), Vec2::new(80.0, 20.0)),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+                Visibility::Visible,
+            ));
+            e.spawn(flak_turret(asset_server, Vec3::new(0.0, 10.0, 1.0)))
+                .with_child(turret_barrel());
+        });
+}
+
+fn flak_turret(asset_server: &Res<AssetServer>, offset: Vec3) -> impl Bundle {
+    (
+        Turret::default(),
+        AimError::default(),
+        Intent::default(),
+        Transform::from_translation(offset),
+        Visibility::Visible,
+        WeaponData {
+            timer: Timer::new(Duration::from_millis(1500), TimerMode::Repeating),
+            damage: 10.0,
+            spread: 0.3,
+            subtype: WeaponSubtype::BulletBased {
+                velocity: Vec3::new(0.0, 500.0, 0.0),
+                gravity: Vec3::new(0.0, -2.0, 0.0),
+                bullet_mass: 0.01,
+                friction: 1.0,
+                bullet_scale: 0.6,
+                num_spawned_per_shot: 3,
+            },
+            lifetime: Duration::from_millis(1500),
+            ..WeaponType::MachineGun
+                .data_from_type_and_handle(asset_server.get_handle("images/bullet.png").unwrap())
+        },
+    )
+}
+
+fn turret_barrel() -> impl Bundle {
+    (
+        Sprite::from_color(Color::srgb(0.2, 0.2, 0.25), Vec2::new(6.0, 24.0)),
+        Transform::from_xyz(0.0, 12.0, 0.0),
+    )
+}
+
+pub fn add_destroyer_enemy(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    game_config: &GameConfig,
+) {
+    let position = Vec3::new(position.x, game_config.lower_bound, 0.0);
+
+    commands
+        .spawn((
+            Visibility::Visible,
+            Transform::from_translation(position),
+            RunScoped,
+            AI::new(AIType::Boat),
+            Intent::default(),
+            HP {
+                hp: 600.0,
+                max: 600.0,
+                regen: 0.0,
+            },
+            BoatStats {
+                aim_speed: 1.0,
+                acceleration: 1.5,
+                friction: 0.985,
+            },
+            Enemy {
+                enemy_type: EnemyType::Destroyer,
+                score: 80,
+                heat: 1.5,
+            },
+            Physics {
+                mass: 5000.0,
+                velocity: Vec3::ZERO,
+                gravity: Vec3::ZERO,
+                friction: 1.0,
+                ..Default::default()
+            },
+            // the hull's hitbox only covers the middle of the ship, the turrets cover the rest.
+            CollisionRadius(40.0),
+        ))
+        .with_children(|e| {
+            // hull
+            e.spawn((
+                Sprite::from_color(Color::srgb(0.25, 0.25, 0.3), Vec2::new(240.0, 30.0)),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+                Visibility::Visible,
+            ));
+            // turrets are weak points that can be destroyed independently of the hull
+            for turret_x in [-80.0, 0.0, 80.0] {
+                e.spawn((
+                    flak_turret(asset_server, Vec3::new(turret_x, 15.0, 1.0)),
+                    EnemyPart,
+                    Enemy {
+                        enemy_type: EnemyType::DestroyerTurret,
+                        score: 15,
+                        heat: 0.25,
+                    },
+                    HP {
+                        hp: 120.0,
+                        max: 120.0,
+                        regen: 0.0,
+                    },
+                    CollisionRadius(15.0),
+                ))
+                .with_child(turret_barrel());
+            }
+        });
+}
+
+// cruises below the water surface, out of reach of most weapons, and periodically surfaces to launch homing missiles.
+pub fn add_submarine_enemy(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    position: Vec3,
+    game_config: &GameConfig,
+) {
+    // submarines start out submerged
+    let position = Vec3::new(position.x, game_config.lower_bound - SUBMARINE_DEPTH, 0.0);
+
+    commands
+        .spawn((
+            Visibility::Visible,
+            Transform::from_translation(position),
+            RunScoped,
+            AI::new(AIType::Submarine),
+            SubmarineState::default(),
+            Submersible,
+            Intent::default(),
+            HP {
+                hp: 200.0,
+                max: 200.0,
+                regen: 0.0,
+            },
+            BoatStats {
+                aim_speed: 0.0,
+                acceleration: 2.5,
+                friction: 0.98,
+            },
+            Enemy {
+                enemy_type: EnemyType::Submarine,
+                score: 50,
+                heat: 1.0,
+            },
+            Physics {
+                mass: 800.0,
+                velocity: Vec3::ZERO,
+                gravity: Vec3::ZERO,
+                friction: 1.0,
+                ..Default::default()
+            },
+            WeaponData {
+                // a salvo of missiles each time it surfaces
+                timer: Timer::new(Duration::from_millis(700), TimerMode::Repeating),
+                ..WeaponType::Missile.data_from_type_and_handle(
+                    asset_server.get_handle("images/bullet.png").unwrap(),
+                )
+            },
+            CollisionRadius(20.0),
+        ))
+        .with_children(|e| {
+            // hull
+            e.spawn((
+                Sprite::from_color(Color::srgb(0.2, 0.25, 0.3), Vec2::new(100.0, 20.0)),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+                Visibility::Visible,
+            ));
+            // conning tower
+            e.spawn((
+                Sprite::from_color(Color::srgb(0.2, 0.25, 0.3), Vec2::new(20.0, 16.0)),
+                Transform::from_xyz(10.0, 16.0, 1.0),
+                Visibility::Visible,
+            ));
+        });
+}
+
+// boats ride along the water surface. they have no gravity, and are pinned to the lower bound.
+pub fn boat_intent_movement_system(
+    game_config: Res<GameConfig>,
+    mut query: Query<(&Intent, &BoatStats, &mut Physics, &mut Transform), Without<SubmarineState>>,
+) {
+    for (intent, stats, mut physics, mut transform) in query.iter_mut() {
+        if intent.accelerate {
+            physics.velocity.x -= intent.turn_intent * stats.acceleration;
+        }
+        if intent.brake {
+            physics.velocity.x *= 0.975;
+        }
+        // water drag
+        physics.velocity.x *= stats.friction;
+        physics.velocity.y = 0.0;
+        transform.translation.y = game_config.lower_bound;
+    }
+}
+
+// submarines drive like boats, but move between their cruising depth and the surface.
+pub fn submarine_movement_system(
+    time: Res<Time>,
+    game_config: Res<GameConfig>,
+    mut query: Query<(
+        &Intent,
+        &BoatStats,
+        &SubmarineState,
+        &mut Physics,
+        &mut Transform,
+    )>,
+) {
+    // vertical speed when diving or surfacing
+    let dive_speed = 80.0;
+    for (intent, stats, state, mut physics, mut transform) in query.iter_mut() {
+        if intent.accelerate {
+            physics.velocity.x -= intent.turn_intent * stats.acceleration;
+        }
+        if intent.brake {
+            physics.velocity.x *= 0.975;
+        }
+        physics.velocity.x *= stats.friction;
+        physics.velocity.y = 0.0;
+
+        let target = state.target_height(&game_config);
+        let max_step = dive_speed * time.delta_secs();
+        transform.translation.y += (target - transform.translation.y).clamp(-max_step, max_step);
+    }
+}
+
 // this mirrors the player hp system to an extent.
 
 pub fn enemy_death_detection_system(
     // mut commands: Commands,
     query: Query<(Entity, &mut HP, &Enemy)>,
     mut events: EventWriter<EnemyDeath>,
+    mut hit_events: EventReader<EnemyHit>,
+    // whatever last hit each enemy, so deaths can be attributed to it
+    mut last_hits: Local<HashMap<Entity, DamageSource>>,
 ) {
+    for event in hit_events.read() {
+        last_hits.insert(event.entity, event.source);
+    }
+    last_hits.retain(|entity, _| query.contains(*entity));
+
     for (entity, hp, enemy) in query.iter() {
         if hp.hp <= 0.0 {
             // kill enemy if hp drops <= 0
             events.write(EnemyDeath {
                 entity,
+                enemy_type: enemy.enemy_type,
                 score: enemy.score,
                 heat: enemy.heat,
+                killed_by: last_hits.get(&entity).copied(),
             });
             // commands.entity(entity).despawn();
         }
@@ -118,9 +653,22 @@ pub fn enemy_death_detection_system(
 pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (plane_ai, enemy_death_detection_system).run_if(in_state(GameState::InGame)),
-        );
+        app.init_resource::<AIBehaviours>()
+            .add_systems(
+                Update,
+                (
+                    (ai_transition_system, ai_intent_system).chain(),
+                    boat_ai,
+                    submarine_ai,
+                    aim_error_system,
+                    turret_aim_system,
+                    enemy_death_detection_system,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (boat_intent_movement_system, submarine_movement_system).run_if(in_game_no_hitstun),
+            );
     }
 }
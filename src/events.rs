@@ -2,7 +2,7 @@
 
 use bevy::prelude::*;
 
-use crate::mods::guns::WeaponType;
+use crate::{enemy::EnemyType, mods::guns::WeaponType};
 
 // pub struct BulletFired<T> {
 //     // where T is the type of bullet fired
@@ -44,23 +44,85 @@ impl WeaponFired {
     }
 }
 
+// what kind of damage a hit did
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DamageType {
+    Kinetic,
+    Explosive,
+    Energy,
+}
+
+// who dealt some damage, and with what. carried by projectiles so that hits can be attributed to whoever fired them.
+#[derive(Copy, Clone, Debug)]
+pub struct DamageSource {
+    // the entity that fired the weapon. it may have been despawned since.
+    pub entity: Entity,
+    pub weapon_type: WeaponType,
+    pub damage_type: DamageType,
+}
+
 #[derive(Default, Event)]
-pub struct PlayerDeath;
+pub struct PlayerDeath {
+    // the last thing that hit the player, if anything did
+    pub killed_by: Option<DamageSource>,
+}
 
 #[derive(Event)]
 pub struct EnemyDeath {
     pub entity: Entity,
+    pub enemy_type: EnemyType,
     pub score: usize,
     pub heat: f32,
+    // the last thing that hit the enemy, if anything did
+    pub killed_by: Option<DamageSource>,
 }
 
-#[derive(Default, Event)]
-pub struct PlayerHit {}
+#[derive(Event)]
+pub struct PlayerHit {
+    pub damage: f32,
+    pub source: DamageSource,
+    // where the hit landed, in world space
+    pub position: Vec3,
+    // set for every tick after the first that a beam keeps touching the player. sustained hits don't cause hitstun.
+    pub sustained: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum VerticalBound {
+    Upper,
+    Lower,
+}
+
+// continuous hp drain from flying out of bounds. kept separate from PlayerHit so that it doesn't cause hitstun.
+#[derive(Event)]
+pub struct PlayerDrained {
+    pub damage: f32,
+    pub bound: VerticalBound,
+}
+
+// indices into GameConfig::heat_tiers
+#[derive(Event)]
+pub struct HeatTierChanged {
+    pub previous: usize,
+    pub current: usize,
+}
 
 #[derive(Event)]
 pub struct EnemyHit {
     pub entity: Entity,
     pub damage: f32,
+    pub source: DamageSource,
+    // where the hit landed, in world space
+    pub position: Vec3,
+}
+
+// a hostile projectile was shot down
+#[derive(Event)]
+pub struct ProjectileIntercepted {
+    pub position: Vec3,
+    // what shot it down
+    pub source: DamageSource,
+    pub score: usize,
 }
 
 pub struct EventsPlugin;
@@ -68,7 +130,10 @@ impl Plugin for EventsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<WeaponFired>()
             .add_event::<PlayerHit>()
+            .add_event::<PlayerDrained>()
             .add_event::<EnemyHit>()
+            .add_event::<ProjectileIntercepted>()
+            .add_event::<HeatTierChanged>()
             .add_event::<PlayerDeath>()
             .add_event::<EnemyDeath>();
     }
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{enemy::Enemy, mods::guns::Bullet};
+use crate::userdata::UserData;
 // use bevy::time::Timer;
 
 #[allow(dead_code)]
@@ -12,7 +12,8 @@ pub enum GameState {
     InHanger,   // can transition to ingame or main menu
     InGame,     // can transition to game ending and hitstun
     HitStun,    // can transition to ingame
-    Paused,     // can transition to game ending and quitting
+    Paused,     // can transition to ingame, restarting and main menu
+    Restarting, // tears down the current run, then transitions to ingame
     GameEnding, // can transition to mainmenu or inhanger
     Quitting,   // quits the game, saving player data to disk and despawning all entities
 }
@@ -20,21 +21,9 @@ pub enum GameState {
 #[derive(Resource, DerefMut, Deref)]
 pub struct GameEndingTimer(pub Timer);
 
-pub fn game_ending_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut timer: ResMut<GameEndingTimer>,
-    mut game_state: ResMut<NextState<GameState>>,
-    enemy_query: Query<Entity, With<Enemy>>,
-    bullet_query: Query<Entity, With<Bullet>>,
-) {
-    timer.tick(time.delta());
-    for entity in enemy_query.iter().chain(bullet_query.iter()) {
-        commands.entity(entity).despawn();
-    }
-
-    if timer.finished() {
-        game_state.set(GameState::MainMenu);
-        timer.reset();
+pub fn quitting_system(userdata: Res<UserData>, mut exit: EventWriter<AppExit>) {
+    if let Err(e) = userdata.save() {
+        error!("failed to save userdata before quitting: {e}");
     }
+    exit.write(AppExit::Success);
 }
@@ -4,7 +4,7 @@ use crate::{player::Player, userdata::UserData};
 use serde::{Deserialize, Serialize};
 
 #[allow(dead_code)]
-#[derive(Default, Clone, Copy, Serialize, Deserialize)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum InputMode {
     #[default]
     Keyboard,
@@ -23,8 +23,7 @@ pub struct Intent {
 
 pub fn player_input_intent_system(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    // axis: Res<Axis<GamepadAxis>>,
-    // buttons_input: Res<ButtonInput<GamepadButton>>,
+    gamepads: Query<&Gamepad>,
     userdata: Res<UserData>,
     mut query: Query<(Entity, &mut Intent), With<Player>>,
 ) -> Result<(), BevyError> {
@@ -32,16 +31,8 @@ pub fn player_input_intent_system(
 
     match userdata.selected_input_method {
         InputMode::Keyboard => {
-            if keyboard_input.just_pressed(KeyCode::Space) {
-                intent.just_fired = true;
-            } else {
-                intent.just_fired = false;
-            }
-            if keyboard_input.pressed(KeyCode::Space) {
-                intent.fire = true;
-            } else {
-                intent.fire = false;
-            }
+            intent.just_fired = keyboard_input.just_pressed(KeyCode::Space);
+            intent.fire = keyboard_input.pressed(KeyCode::Space);
 
             if keyboard_input.pressed(KeyCode::ArrowUp) {
                 // accelerate
@@ -68,7 +59,23 @@ pub fn player_input_intent_system(
             }
         }
         InputMode::Controller => {
-            panic!();
+            // use whichever gamepad was connected first
+            let Some(gamepad) = gamepads.iter().next() else {
+                *intent = Intent::default();
+                return Ok(());
+            };
+            intent.just_fired = gamepad.just_pressed(GamepadButton::South);
+            intent.fire = gamepad.pressed(GamepadButton::South);
+            intent.accelerate = gamepad.pressed(GamepadButton::RightTrigger2);
+            intent.brake = gamepad.pressed(GamepadButton::LeftTrigger2);
+
+            let stick_x = gamepad.left_stick().x;
+            intent.turn_intent = if stick_x.abs() > userdata.deadzone_radius {
+                // stick right turns right, which is a negative turn intent
+                -stick_x
+            } else {
+                0.0
+            };
         }
     }
     Ok(())
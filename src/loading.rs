@@ -1,4 +1,3 @@
-use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -7,11 +6,14 @@ use bevy::asset::{LoadState, LoadedFolder};
 use bevy::scene::ron::ser::Serializer;
 use bevy::{asset::RecursiveDependencyLoadState, prelude::*, scene::ron::ser::PrettyConfig};
 
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 // use bevy_kira_audio::AudioSource;
 
 use crate::sprite::TextureAtlasHashMap;
-use crate::{config::GameConfig, gamestate::GameState, userdata::UserData};
+use crate::{
+    ai::state_machine::AIBehaviours, background::BackgroundLayers, config::GameConfig,
+    gamestate::GameState, misc::waves::WaveTable, userdata::UserData,
+};
 
 #[derive(Resource, Deref)]
 pub struct AssetsTracking(pub Vec<UntypedHandle>);
@@ -44,7 +46,7 @@ pub fn bake_assets(
     mut loading: ResMut<AssetsTracking>,
     mut atlas_map: ResMut<TextureAtlasHashMap>,
 ) -> Result<(), BevyError> {
-    if intermediate.len() == 0 {
+    if intermediate.is_empty() {
         let mut targets = targets
             .iter()
             .filter_map(|e| {
@@ -95,7 +97,7 @@ pub fn bake_assets(
             let mut binding = TextureAtlasBuilder::default();
             let builder = binding.auto_format_conversion(true);
 
-            let (target_png, target_ron, folder_handle, start_time) = intermediate.swap_remove(idx);
+            let (target_png, _target_ron, folder_handle, start_time) = intermediate.swap_remove(idx);
 
             let key = target_png
                 .file_stem()
@@ -116,7 +118,7 @@ pub fn bake_assets(
                 builder.add_texture(None, image);
             }
 
-            let (layout, sources, actual_atlas) = builder.build()?;
+            let (layout, _sources, actual_atlas) = builder.build()?;
 
             // TODO: add serialization and deserialization for Atlases. Needs research into how to serialize a Image, as the existing mechanisms seem clunky
             // warn!("writing layout file to {:?}", target_ron);
@@ -139,8 +141,11 @@ pub fn bake_assets(
     Ok(())
 }
 
-const GAME_CONFIG_FILE: &'static str = "config.ron";
-const USER_CONFIG_FILE: &'static str = "userdata.ron";
+const GAME_CONFIG_FILE: &str = "config.ron";
+pub const USER_CONFIG_FILE: &str = "userdata.ron";
+const AI_BEHAVIOURS_FILE: &str = "ai.ron";
+const WAVE_TABLE_FILE: &str = "waves.ron";
+const BACKGROUND_FILE: &str = "background.ron";
 
 pub fn load_assets(
     asset_server: Res<AssetServer>,
@@ -148,7 +153,10 @@ pub fn load_assets(
 ) -> Result<(), BevyError> {
     // static sprites
     for image_path in [
-        "images/background.png",
+        "images/sky.png",
+        "images/clouds_far.png",
+        "images/clouds_near.png",
+        "images/water.png",
         "images/bullet.png",
         "images/enemy/basic_enemy.png",
     ] {
@@ -156,10 +164,8 @@ pub fn load_assets(
         loading.add(handle.untyped());
     }
 
-    for audio_path in ["sfx/hit_sound.ogg"] {
-        let handle: Handle<AudioSource> = asset_server.load(audio_path);
-        loading.add(handle.clone().untyped());
-    }
+    let handle: Handle<AudioSource> = asset_server.load("sfx/hit_sound.ogg");
+    loading.add(handle.untyped());
     // stats
     let path = Path::new("assets").join(USER_CONFIG_FILE);
     if let Ok(file) = std::fs::File::create_new(path) {
@@ -179,10 +185,43 @@ pub fn load_assets(
         result.expect("could not write to file");
     }
 
+    let path = Path::new("assets").join(AI_BEHAVIOURS_FILE);
+    if let Ok(file) = std::fs::File::create_new(path) {
+        // will error if the file already exists
+        let mut serializer = Serializer::new(file, Some(PrettyConfig::new().depth_limit(6)))
+            .expect("couldn't create serializer");
+        let result = AIBehaviours::default().serialize(&mut serializer);
+        result.expect("could not write to file");
+    }
+
+    let path = Path::new("assets").join(WAVE_TABLE_FILE);
+    if let Ok(file) = std::fs::File::create_new(path) {
+        // will error if the file already exists
+        let mut serializer = Serializer::new(file, Some(PrettyConfig::new().depth_limit(5)))
+            .expect("couldn't create serializer");
+        let result = WaveTable::default().serialize(&mut serializer);
+        result.expect("could not write to file");
+    }
+
+    let path = Path::new("assets").join(BACKGROUND_FILE);
+    if let Ok(file) = std::fs::File::create_new(path) {
+        // will error if the file already exists
+        let mut serializer = Serializer::new(file, Some(PrettyConfig::new().depth_limit(4)))
+            .expect("couldn't create serializer");
+        let result = BackgroundLayers::default().serialize(&mut serializer);
+        result.expect("could not write to file");
+    }
+
     let handle: Handle<UserData> = asset_server.load(USER_CONFIG_FILE);
     loading.add(handle.untyped());
     let handle: Handle<GameConfig> = asset_server.load(GAME_CONFIG_FILE);
     loading.add(handle.untyped());
+    let handle: Handle<AIBehaviours> = asset_server.load(AI_BEHAVIOURS_FILE);
+    loading.add(handle.untyped());
+    let handle: Handle<WaveTable> = asset_server.load(WAVE_TABLE_FILE);
+    loading.add(handle.untyped());
+    let handle: Handle<BackgroundLayers> = asset_server.load(BACKGROUND_FILE);
+    loading.add(handle.untyped());
 
     info!("loading {} items", loading.0.len());
     Ok(())
@@ -213,11 +252,17 @@ pub fn loading_state_watcher<T: Asset>(mut loads: EventReader<AssetEvent<T>>) {
 pub fn loading_update(
     mut game_config: ResMut<GameConfig>,
     mut user_data: ResMut<UserData>,
+    mut ai_behaviours: ResMut<AIBehaviours>,
+    mut wave_table: ResMut<WaveTable>,
+    mut background: ResMut<BackgroundLayers>,
     mut state: ResMut<NextState<GameState>>,
     server: Res<AssetServer>,
     loading: Res<AssetsTracking>,
     game_config_asset: Res<Assets<GameConfig>>,
     user_data_asset: Res<Assets<UserData>>,
+    ai_behaviours_asset: Res<Assets<AIBehaviours>>,
+    wave_table_asset: Res<Assets<WaveTable>>,
+    background_asset: Res<Assets<BackgroundLayers>>,
     targets: Res<BakeTargetsIntermediate>,
     // atlas_map: Res<TextureAtlasHashMap>,
 ) {
@@ -242,7 +287,7 @@ pub fn loading_update(
         }
     }
 
-    if targets.len() > 0 {
+    if !targets.is_empty() {
         all_done = false;
     }
 
@@ -257,6 +302,21 @@ pub fn loading_update(
             .unwrap()
             .clone();
 
+        *ai_behaviours = ai_behaviours_asset
+            .get(server.get_handle(AI_BEHAVIOURS_FILE).unwrap().id())
+            .unwrap()
+            .clone();
+
+        *wave_table = wave_table_asset
+            .get(server.get_handle(WAVE_TABLE_FILE).unwrap().id())
+            .unwrap()
+            .clone();
+
+        *background = background_asset
+            .get(server.get_handle(BACKGROUND_FILE).unwrap().id())
+            .unwrap()
+            .clone();
+
         state.set(GameState::MainMenu);
     }
 }
@@ -12,7 +12,7 @@ use tracing_subscriber::layer::SubscriberExt;
 static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
 
 impl Plugin for CustomLogPlugin {
-    fn build(&self, app: &mut App) {
+    fn build(&self, _app: &mut App) {
         use bevy::log::tracing_subscriber::Registry;
         let finished_subscriber;
         let subscriber = Registry::default();
@@ -40,6 +40,6 @@ impl Plugin for CustomLogPlugin {
         let subscriber = subscriber.with(fmt_layer);
         finished_subscriber = subscriber;
 
-        let already_set = set_global_default(finished_subscriber).is_err();
+        let _ = set_global_default(finished_subscriber);
     }
 }
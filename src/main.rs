@@ -9,6 +9,7 @@ use bevy_common_assets::ron::RonAssetPlugin;
 // use bevy_kira_audio::prelude::*;
 
 mod ai;
+mod background;
 mod body_type_stats;
 mod camera;
 mod config;
@@ -22,6 +23,7 @@ mod misc;
 mod mods;
 mod physics;
 mod player;
+mod reset;
 mod sfx;
 mod sprite;
 mod ui;
@@ -29,11 +31,13 @@ mod userdata;
 mod vfx;
 
 // use bevy_egui::EguiPlugin;
+use ai::state_machine::AIBehaviours;
+use background::{BackgroundLayers, BackgroundPlugin, setup_background};
 use camera::CameraPlugin;
 use config::GameConfig;
 use enemy::EnemyPlugin;
 use events::EventsPlugin;
-use gamestate::{GameEndingTimer, GameState, game_ending_system};
+use gamestate::{GameEndingTimer, GameState, quitting_system};
 use input::player_input_intent_system;
 use loading::{
     AssetsTracking, BakeTargets, BakeTargetsIntermediate, bake_assets, load_assets, loading_update,
@@ -44,7 +48,9 @@ use misc::{
     hitstun::{HitStun, in_game_no_hitstun},
     hp_regen_system, lifetime_postprocess_system, lifetime_system,
     score::ScorePlugin,
+    stats::StatsPlugin,
     vertical_bound_system,
+    waves::WaveTable,
 };
 use mods::{
     BodyModsPlugin,
@@ -53,8 +59,9 @@ use mods::{
 use physics::linear_physics;
 use player::{
     add_player, animate_player_sprite, plane_intent_movement_system, player_death_detection_system,
-    player_death_system_stage_one, player_death_system_stage_two,
+    player_death_system_stage_one, turn_angle_rotation_system,
 };
+use reset::RunResetPlugin;
 use sfx::Sfx as SfxPlugin;
 use sprite::TextureAtlasHashMap;
 use vfx::{VfxPlugin, hp::hp_effect_setup_system};
@@ -63,14 +70,6 @@ use userdata::UserData;
 
 use crate::{loading::loading_state_watcher, ui::GameUIPlugin};
 
-fn setup_background(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Sprite {
-        image: asset_server.get_handle("images/background.png").unwrap(),
-
-        ..Default::default()
-    }); // TODO: change this to a dynamic background that adapts to where the player is, such that an infinite scrolling effect can be achieved.
-}
-
 #[derive(Resource, DerefMut, Deref)]
 pub struct DebugTimer(Timer);
 
@@ -112,7 +111,7 @@ fn main() {
             paths: vec![Path::new("images").join("pre_atlas").join("player")],
         })
         .insert_resource(BakeTargetsIntermediate::default())
-        .insert_resource(HitStun(false))
+        .init_resource::<HitStun>()
         .insert_resource(UserData::default())
         .insert_resource(GameConfig::default())
         .insert_resource(GameEndingTimer(Timer::new(
@@ -122,15 +121,21 @@ fn main() {
         // insert system to handle userdata loading and saving
         .add_plugins(RonAssetPlugin::<UserData>::new(&["userdata.ron"]))
         .add_plugins(RonAssetPlugin::<GameConfig>::new(&["config.ron"]))
+        .add_plugins(RonAssetPlugin::<AIBehaviours>::new(&["ai.ron"]))
+        .add_plugins(RonAssetPlugin::<WaveTable>::new(&["waves.ron"]))
+        .add_plugins(RonAssetPlugin::<BackgroundLayers>::new(&["background.ron"]))
         .add_plugins((
             EventsPlugin,
             VfxPlugin,
             SfxPlugin,
             MiscPlugin,
             ScorePlugin,
+            StatsPlugin,
+            RunResetPlugin,
             BodyModsPlugin,
             EnemyPlugin,
             CameraPlugin,
+            BackgroundPlugin,
             GunCollectionPlugin,
             WeaponSubsystemPlugin,
             GameUIPlugin, // depends on PausePlugin, automatically adds it
@@ -138,6 +143,7 @@ fn main() {
                           // FrameTimeDiagnosticsPlugin::default(),
         ))
         .add_systems(OnEnter(GameState::Loading), load_assets)
+        .add_systems(OnEnter(GameState::Quitting), quitting_system)
         .add_systems(
             Update,
             (
@@ -146,6 +152,9 @@ fn main() {
                 loading_state_watcher::<Image>,
                 loading_state_watcher::<GameConfig>,
                 loading_state_watcher::<UserData>,
+                loading_state_watcher::<AIBehaviours>,
+                loading_state_watcher::<WaveTable>,
+                loading_state_watcher::<BackgroundLayers>,
                 loading_state_watcher::<AudioSource>,
             )
                 .run_if(in_state(GameState::Loading)),
@@ -157,6 +166,13 @@ fn main() {
             },
             (setup_background, add_player),
         )
+        .add_systems(
+            OnTransition {
+                exited: GameState::Restarting,
+                entered: GameState::InGame,
+            },
+            (setup_background, add_player),
+        )
         // // setup and update for in-game
         .add_systems(
             Update,
@@ -164,6 +180,7 @@ fn main() {
                 player_input_intent_system,
                 animate_player_sprite,
                 plane_intent_movement_system,
+                turn_angle_rotation_system,
                 linear_physics,
             )
                 .chain()
@@ -181,11 +198,6 @@ fn main() {
             )
                 .run_if(in_game_no_hitstun),
         )
-        .add_systems(
-            Update,
-            (game_ending_system, player_death_system_stage_two)
-                .run_if(in_state(GameState::GameEnding)),
-        )
         .add_systems(PostUpdate, lifetime_postprocess_system)
         .run();
 }
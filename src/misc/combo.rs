@@ -19,6 +19,14 @@ impl ComboCounter {
     pub fn multiplier(&self) -> f32 {
         (1.0 + self.count as f32).min(20.0)
     }
+    pub fn reset(&mut self) {
+        self.decay_timer.reset();
+        self.count = 0;
+    }
+    // fraction of the decay timer that is left before the combo resets
+    pub fn decay_fraction_remaining(&self) -> f32 {
+        self.decay_timer.fraction_remaining()
+    }
 }
 
 pub fn combo_enemy_death_subscriber(
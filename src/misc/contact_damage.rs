@@ -1,15 +1,52 @@
-
 use bevy::prelude::*;
+
+use crate::{
+    misc::{CollisionRadius, HP, SpatialHash},
+    physics::Physics,
+};
+
 #[derive(Component)]
 pub struct TakesContactDamage {}
 
+// damage is dealt continuously for as long as the two touch, so it doesn't cause hitstun
 #[derive(Component)]
-pub struct DealsContactDamage {}
+pub struct DealsContactDamage {
+    pub damage_per_second: f32,
+}
 
 pub fn contact_damage_system<Takers: Component, Dealers: Component>(
-    mut query_damage_takers: Query<(Entity, &mut Takers, &TakesContactDamage)>,
-    query_damage_dealers: Query<(Entity, &Dealers, &DealsContactDamage)>,
+    time: Res<Time>,
+    spatial_hash: Res<SpatialHash>,
+    mut query_damage_takers: Query<
+        (
+            Entity,
+            &mut HP,
+            &Transform,
+            &CollisionRadius,
+            Option<&Physics>,
+        ),
+        (With<Takers>, With<TakesContactDamage>),
+    >,
+    query_damage_dealers: Query<&DealsContactDamage, With<Dealers>>,
 ) {
-    // check collisions between takers and dealers.
-    // ideally we would have an acceleration structure here to make collision checks faster
+    // check collisions between takers and the dealers near them.
+    // takers are swept along their last step, and the hash sweeps dealers along theirs,
+    // so fast planes can't pass through each other between frames.
+    for (entity, mut hp, transform, &radius, physics) in query_damage_takers.iter_mut() {
+        let previous = physics
+            .map(|physics| physics.previous_translation(transform))
+            .unwrap_or(transform.translation);
+        for (_, entry) in spatial_hash.query_sweep(
+            previous.truncate(),
+            transform.translation.truncate(),
+            *radius,
+        ) {
+            if entry.entity == entity {
+                continue;
+            }
+            if let Ok(dealer) = query_damage_dealers.get(entry.entity) {
+                hp.hp -= dealer.damage_per_second * time.delta_secs();
+            }
+        }
+    }
 }
@@ -1,98 +1,214 @@
 use bevy::prelude::*;
 
+use super::{
+    CollisionRadius, HP,
+    combo::ComboCounter,
+    heat::HeatTracker,
+    waves::{WaveTable, random_spawn_direction},
+};
 use crate::{
-    enemy::{Enemy, add_basic_enemy},
-    events::{EnemyDeath, PlayerDeath},
+    ai::submarine::SUBMARINE_DEPTH,
+    config::GameConfig,
+    enemy::{Enemy, EnemyPart, SpawnArea, Turret, spawn_enemy},
+    events::EnemyDeath,
+    mods::guns::WeaponData,
     player::Player,
 };
 
 // wave system
 
-#[derive(Resource)]
-pub struct HeatTracker {
-    // waves should spawn more frequently when heat is high.
-    // waves should advance through a few archetypes, where early waves only spawn basic enemies and further waves spawn strong enemies.
-    time_since_last_wave: f32,
-    heat: f32,
-    spawned_waves: u32,
-}
-
-impl Default for HeatTracker {
-    fn default() -> Self {
-        HeatTracker {
-            // TODO: marking this as the place where the timing for the first wave is currently implemented.
-            time_since_last_wave: 55.0,
-            heat: 1.0,
-            spawned_waves: 0,
-        }
-    }
-}
-
-impl HeatTracker {
-    pub fn reset(&mut self) {
-        *self = Self::default();
-    }
-}
-
-pub fn heat_player_death_subscriber(
-    mut heat_tracker: ResMut<HeatTracker>,
-    mut events: EventReader<PlayerDeath>,
-) {
-    if !events.is_empty() {
-        events.clear();
-        heat_tracker.reset();
-    }
-}
+// how far outside the visible area enemies spawn
+const OFFSCREEN_MARGIN: f32 = 80.0;
+// air enemies don't spawn closer than this to the bounds
+const SPAWN_BOUND_MARGIN: f32 = 50.0;
+const MIN_PLAYER_DISTANCE: f32 = 400.0;
 
 pub fn heat_enemy_death_subscriber(
     mut commands: Commands,
     mut heat_tracker: ResMut<HeatTracker>,
+    game_config: Res<GameConfig>,
+    combo: Res<ComboCounter>,
     mut events: EventReader<EnemyDeath>,
-    query: Query<(Entity, &Enemy)>,
+    query: Query<(Entity, Has<EnemyPart>), With<Enemy>>,
 ) {
     for event in events.read() {
         // make sure this enemy has not already been despawned for some reason.
-        if query.contains(event.entity) {
+        if let Ok((entity, is_part)) = query.get(event.entity) {
             // spawn fx for death
             // queue sound playing
-            // despawn enemy
-            commands.entity(event.entity).despawn();
-            // handle `heat`
-            heat_tracker.heat += event.heat;
+            if is_part {
+                // disable the part, leaving the parent intact.
+                // the parent may die on the same frame, so these need to tolerate the entity being despawned.
+                commands
+                    .entity(entity)
+                    .try_remove::<(Enemy, HP, CollisionRadius, WeaponData, Turret)>()
+                    .try_insert(Visibility::Hidden);
+            } else {
+                // despawn enemy
+                commands.entity(entity).despawn();
+            }
+            // handle `heat`. kills during a combo heat things up faster.
+            let combo_bonus = 1.0 + combo.count as f32 * game_config.heat_combo_bonus;
+            heat_tracker.add_heat(event.heat * combo_bonus);
         }
     }
 }
 
+// the part of the world the camera can currently see
+pub fn visible_world_rect(camera: &Camera, camera_transform: &GlobalTransform) -> Option<Rect> {
+    let viewport = camera.logical_viewport_rect()?;
+    let a = camera
+        .viewport_to_world_2d(camera_transform, viewport.min)
+        .ok()?;
+    let b = camera
+        .viewport_to_world_2d(camera_transform, viewport.max)
+        .ok()?;
+    Some(Rect::from_corners(a, b))
+}
+
+// moves a desired spawn position to just outside the visible area, keeping it within the spawn area and away from the player.
+// `margin` is how far outside the visible area the position needs to be.
+pub fn place_off_screen(
+    desired: Vec3,
+    area: SpawnArea,
+    visible: Rect,
+    margin: f32,
+    player_position: Vec3,
+    game_config: &GameConfig,
+) -> Vec3 {
+    let air_min = game_config.lower_bound + SPAWN_BOUND_MARGIN;
+    let air_max = game_config.upper_bound - SPAWN_BOUND_MARGIN;
+    let mut position = desired.truncate();
+    position.y = match area {
+        SpawnArea::Air => position.y.clamp(air_min, air_max),
+        SpawnArea::Surface => game_config.lower_bound,
+        SpawnArea::Underwater => game_config.lower_bound - SUBMARINE_DEPTH,
+    };
+
+    let outside = visible.inflate(margin);
+    if outside.contains(position) {
+        // leave through whichever edge is closest. water enemies can only leave to the sides.
+        let mut exits = vec![
+            Vec2::new(outside.min.x, position.y),
+            Vec2::new(outside.max.x, position.y),
+        ];
+        if area == SpawnArea::Air {
+            if outside.max.y <= air_max {
+                exits.push(Vec2::new(position.x, outside.max.y));
+            }
+            if outside.min.y >= air_min {
+                exits.push(Vec2::new(position.x, outside.min.y));
+            }
+        }
+        position = exits
+            .into_iter()
+            .min_by(|a, b| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            })
+            .unwrap_or(position);
+    }
+
+    // on a small enough screen, off screen can still be too close
+    let from_player = position - player_position.truncate();
+    if from_player.length() < MIN_PLAYER_DISTANCE {
+        let side = if from_player.x < 0.0 { -1.0 } else { 1.0 };
+        let push = match area {
+            // only push sideways, so that water enemies stay in the water
+            SpawnArea::Surface | SpawnArea::Underwater => Vec2::X * side,
+            SpawnArea::Air => from_player.try_normalize().unwrap_or(Vec2::X * side),
+        };
+        position += push * (MIN_PLAYER_DISTANCE - from_player.length());
+        if area == SpawnArea::Air {
+            position.y = position.y.clamp(air_min, air_max);
+        }
+    }
+
+    position.extend(desired.z)
+}
+
 pub fn wave_system(
     mut commands: Commands,
     time: Res<Time>,
     mut heat_tracker: ResMut<HeatTracker>,
     player: Query<&Transform, With<Player>>,
     asset_server: Res<AssetServer>,
+    game_config: Res<GameConfig>,
+    wave_table: Res<WaveTable>,
+    camera: Query<(&Camera, &GlobalTransform)>,
 ) -> Result<(), BevyError> {
-    let player_position = player.single()?; // assumes there's only one player.
-    if heat_tracker.time_since_last_wave > 60.0 / heat_tracker.heat {
+    let player_position = player.single()?.translation; // assumes there's only one player.
+    let tier = game_config.heat_tier(heat_tracker.heat());
+    let interval = if heat_tracker.spawned_waves == 0 {
+        game_config.first_wave_delay
+    } else {
+        let spawn_rate = tier.map(|(_, tier)| tier.spawn_rate).unwrap_or(1.0);
+        heat_tracker.next_wave_interval / spawn_rate
+    };
+    if heat_tracker.time_since_last_wave > interval {
         // spawn wave
-        // enemies need to be relatively close to the player.
+        let tier_name = tier.map(|(_, tier)| tier.name.as_str());
+        let Some(archetype) = wave_table.pick(heat_tracker.spawned_waves, tier_name) else {
+            warn!("no wave archetype for wave {}", heat_tracker.spawned_waves);
+            heat_tracker.time_since_last_wave = 0.0;
+            return Ok(());
+        };
+        info!(
+            "spawning wave {} ({})",
+            heat_tracker.spawned_waves, archetype.name
+        );
 
-        match heat_tracker.spawned_waves {
-            // 0..=4 => {
-            // spawn enemies based on this archetype.
-            // },
-            _ => {
-                for _ in 0..10 {
-                    add_basic_enemy(&mut commands, &asset_server, player_position.translation);
-                }
-            }
+        // the whole wave shares one formation, in the order enemies are listed
+        let enemies = archetype
+            .enemies
+            .iter()
+            .flat_map(|entry| {
+                std::iter::repeat_n(entry.enemy, entry.roll_count(heat_tracker.heat()) as usize)
+            })
+            .collect::<Vec<_>>();
+        // with no camera, treat the player's position as the only visible point
+        let visible = camera
+            .single()
+            .ok()
+            .and_then(|(camera, transform)| visible_world_rect(camera, transform))
+            .unwrap_or(Rect::from_center_size(
+                player_position.truncate(),
+                Vec2::ZERO,
+            ));
+
+        // keep the whole formation off screen, then make sure each enemy is somewhere it's allowed to be
+        let count = enemies.len() as u32;
+        let center = place_off_screen(
+            player_position + random_spawn_direction() * archetype.spawn_distance,
+            SpawnArea::Air,
+            visible,
+            OFFSCREEN_MARGIN + archetype.extent(count),
+            player_position,
+            &game_config,
+        );
+        let positions = archetype.formation_positions(center, player_position, count);
+        for (enemy_type, position) in enemies.into_iter().zip(positions) {
+            let position = place_off_screen(
+                position,
+                enemy_type.spawn_area(),
+                visible,
+                OFFSCREEN_MARGIN,
+                player_position,
+                &game_config,
+            );
+            spawn_enemy(
+                &mut commands,
+                &asset_server,
+                &game_config,
+                enemy_type,
+                position,
+            );
         }
+
+        heat_tracker.next_wave_interval = archetype.interval;
         heat_tracker.spawned_waves += 1;
 
-        // reset time and "lower" heat
         heat_tracker.time_since_last_wave = 0.0;
-        heat_tracker.heat -= 0.01;
-        if heat_tracker.heat < 1.0 {
-            heat_tracker.heat = 1.0;
-        }
     } else {
         heat_tracker.time_since_last_wave += time.delta_secs();
     }
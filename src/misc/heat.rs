@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::{config::GameConfig, events::HeatTierChanged};
+
+use super::combo::ComboCounter;
+
+// heat rises with kills, faster while a combo is going, and cools off over time when there's no combo.
+// the current heat tier (see GameConfig::heat_tiers) decides which waves can spawn, how often they come, and how hard enemies hit.
+#[derive(Resource)]
+pub struct HeatTracker {
+    pub(super) time_since_last_wave: f32,
+    // seconds between the last wave and the next one, before the tier's spawn rate is applied. set by the last wave's archetype.
+    pub(super) next_wave_interval: f32,
+    pub(super) spawned_waves: u32,
+    heat: f32,
+    tier: usize,
+}
+
+// heat never drops below this
+const MIN_HEAT: f32 = 1.0;
+
+impl Default for HeatTracker {
+    fn default() -> Self {
+        HeatTracker {
+            time_since_last_wave: 0.0,
+            next_wave_interval: 0.0,
+            spawned_waves: 0,
+            heat: MIN_HEAT,
+            tier: 0,
+        }
+    }
+}
+
+impl HeatTracker {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+    pub fn heat(&self) -> f32 {
+        self.heat
+    }
+    // index into GameConfig::heat_tiers
+    pub fn tier(&self) -> usize {
+        self.tier
+    }
+    pub fn spawned_waves(&self) -> u32 {
+        self.spawned_waves
+    }
+    pub fn add_heat(&mut self, amount: f32) {
+        self.heat = (self.heat + amount).max(MIN_HEAT);
+    }
+    // multiplier for the damage of enemy weapons at the current tier
+    pub fn damage_multiplier(&self, game_config: &GameConfig) -> f32 {
+        game_config
+            .heat_tiers
+            .get(self.tier)
+            .map(|tier| tier.damage_multiplier)
+            .unwrap_or(1.0)
+    }
+}
+
+pub fn heat_decay_system(
+    time: Res<Time>,
+    game_config: Res<GameConfig>,
+    combo: Res<ComboCounter>,
+    mut heat_tracker: ResMut<HeatTracker>,
+) {
+    // keeping a combo going holds the heat where it is
+    if combo.count == 0 {
+        heat_tracker.add_heat(-game_config.heat_decay_rate * time.delta_secs());
+    }
+}
+
+pub fn heat_tier_system(
+    game_config: Res<GameConfig>,
+    mut heat_tracker: ResMut<HeatTracker>,
+    mut events: EventWriter<HeatTierChanged>,
+) {
+    let Some((tier, _)) = game_config.heat_tier(heat_tracker.heat) else {
+        return;
+    };
+    if tier != heat_tracker.tier {
+        info!("heat tier changed from {} to {}", heat_tracker.tier, tier);
+        events.write(HeatTierChanged {
+            previous: heat_tracker.tier,
+            current: tier,
+        });
+        heat_tracker.tier = tier;
+    }
+}
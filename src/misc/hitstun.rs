@@ -2,17 +2,33 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 
-use crate::{
-    config::GameConfig,
-    events::{EnemyHit, PlayerHit},
-    gamestate::GameState,
-};
+use crate::{config::GameConfig, events::PlayerHit, gamestate::GameState};
 
-#[derive(Copy, Clone, Deref, DerefMut, Default, Resource)]
-pub struct HitStun(pub bool);
+// the timer lives next to the flag so that anything that drops the hitstun also drops its progress
+#[derive(Clone, Default, Resource)]
+pub struct HitStun {
+    pub active: bool,
+    pub timer: Timer,
+}
+
+impl HitStun {
+    pub fn start(&mut self, duration: Duration) {
+        self.active = true;
+        self.timer = Timer::new(duration, TimerMode::Once);
+    }
+
+    pub fn clear(&mut self) {
+        *self = HitStun::default();
+    }
+}
 
 pub fn in_game_no_hitstun(state: Res<State<GameState>>, hitstun: Res<HitStun>) -> bool {
-    state.get() == &GameState::InGame && !(**hitstun)
+    state.get() == &GameState::InGame && !hitstun.active
+}
+
+// for systems that should keep running while the game is frozen by hitstun, like the hud
+pub fn in_game_or_hitstun(state: Res<State<GameState>>) -> bool {
+    matches!(state.get(), GameState::InGame | GameState::HitStun)
 }
 
 pub fn hitstun_trigger_system(
@@ -20,32 +36,28 @@ pub fn hitstun_trigger_system(
     // mut enemy_hit_events: EventReader<EnemyHit>,
     mut gamestate: ResMut<NextState<GameState>>,
     mut hitstun: ResMut<HitStun>,
+    settings: Res<GameConfig>,
 ) {
-    if !player_hit_events.is_empty()  {
-        player_hit_events.clear();
+    // a beam that keeps touching the player would otherwise freeze the game every other frame
+    if player_hit_events
+        .read()
+        .filter(|event| !event.sustained)
+        .count()
+        > 0
+    {
         // enemy_hit_events.clear();
-        **hitstun = true;
+        hitstun.start(Duration::from_millis(settings.hitstun_time_ms as u64));
         gamestate.set(GameState::HitStun);
     }
 }
 
 pub fn hitstun_tick_system(
     time: Res<Time>,
-    mut timer: Local<Option<Timer>>,
     mut hitstun: ResMut<HitStun>,
     mut gamestate: ResMut<NextState<GameState>>,
-    settings: Res<GameConfig>,
 ) {
-    if let Some(timer) = timer.as_mut() {
-        if timer.tick(time.delta()).finished() {
-            timer.reset();
-            **hitstun = false;
-            gamestate.set(GameState::InGame);
-        }
-    } else {
-        *timer = Some(Timer::new(
-            Duration::from_millis(settings.hitstun_time_ms as u64),
-            TimerMode::Once,
-        ));
+    if hitstun.timer.tick(time.delta()).finished() {
+        hitstun.clear();
+        gamestate.set(GameState::InGame);
     }
 }
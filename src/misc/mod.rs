@@ -3,27 +3,43 @@ use std::f32::consts::TAU;
 use bevy::prelude::*;
 use rand::random;
 
-use crate::gamestate::GameState;
+use crate::{
+    enemy::Enemy,
+    gamestate::GameState,
+    mods::guns::{Bullet, Interceptable},
+    physics::Physics,
+    player::Player,
+};
 
 pub mod combo;
-// pub mod contact_damage;
+pub mod contact_damage;
 pub mod enemy_spawning;
+pub mod heat;
 pub mod hitstun;
 pub mod hp;
 pub mod lifetime;
 pub mod score;
+pub mod spatial_hash;
+pub mod stats;
 pub mod vertical_bound;
+pub mod water;
+pub mod waves;
 
-pub use enemy_spawning::HeatTracker;
+pub use heat::HeatTracker;
 pub use hitstun::in_game_no_hitstun;
 pub use hp::{HP, hp_regen_system};
 pub use lifetime::{Lifetime, lifetime_postprocess_system, lifetime_system};
+pub use spatial_hash::SpatialHash;
 pub use vertical_bound::{VerticallyBounded, vertical_bound_system};
 
 use self::{
     combo::{ComboCounter, combo_enemy_death_subscriber},
-    enemy_spawning::{heat_enemy_death_subscriber, heat_player_death_subscriber, wave_system},
+    contact_damage::contact_damage_system,
+    enemy_spawning::{heat_enemy_death_subscriber, wave_system},
+    heat::{heat_decay_system, heat_tier_system},
     hitstun::{hitstun_tick_system, hitstun_trigger_system},
+    water::{bullet_water_system, underwater_detection_system, underwater_visibility_system},
+    waves::WaveTable,
 };
 
 // misc functions
@@ -48,6 +64,38 @@ pub fn random_in_circle() -> Vec2 {
 
 #[derive(Component, Deref, Copy, Clone)]
 pub struct CollisionRadius(pub f32);
+
+// rebuilds the spatial hash from everything that can be hit.
+// bullets aren't inserted, they query the hash instead, unless they can be shot down.
+pub fn spatial_hash_system(
+    mut spatial_hash: ResMut<SpatialHash>,
+    query: Query<
+        (
+            Entity,
+            &Transform,
+            &GlobalTransform,
+            Has<ChildOf>,
+            &CollisionRadius,
+            Option<&Physics>,
+        ),
+        Or<(Without<Bullet>, With<Interceptable>)>,
+    >,
+) {
+    spatial_hash.clear();
+    for (entity, transform, global_transform, is_child, radius, physics) in query.iter() {
+        // bullets are tested against this frame's transforms, but global transforms aren't propagated until after Update.
+        // only children (i.e. turrets) need the global transform, and they lag a frame behind.
+        let position = if is_child {
+            global_transform.translation()
+        } else {
+            transform.translation
+        };
+        let step = physics
+            .map(|physics| physics.last_step.truncate())
+            .unwrap_or(Vec2::ZERO);
+        spatial_hash.insert(entity, position.truncate(), **radius, step);
+    }
+}
 // fn cleanup_system<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
 //     for e in &query {
 //         commands.entity(e).despawn();
@@ -60,18 +108,34 @@ pub struct MiscPlugin;
 impl Plugin for MiscPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<HeatTracker>()
+            .init_resource::<WaveTable>()
+            .init_resource::<SpatialHash>()
             .insert_resource(ComboCounter::new(Timer::from_seconds(4.0, TimerMode::Once)))
             .add_systems(
                 Update,
                 (
                     wave_system,
-                    heat_player_death_subscriber,
                     heat_enemy_death_subscriber,
+                    heat_decay_system,
+                    heat_tier_system,
                     combo_enemy_death_subscriber,
                     hitstun_trigger_system,
+                    underwater_detection_system,
+                    bullet_water_system,
+                    underwater_visibility_system,
                 )
                     .run_if(in_state(GameState::InGame)),
             )
+            .add_systems(
+                Update,
+                (
+                    contact_damage_system::<Player, Enemy>,
+                    contact_damage_system::<Enemy, Player>,
+                )
+                    // the spatial hash is built from this frame's transforms, so wait for everything to move
+                    .after(spatial_hash_system)
+                    .run_if(in_game_no_hitstun),
+            )
             .add_systems(
                 Update,
                 hitstun_tick_system.run_if(in_state(GameState::HitStun)),
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{events::EnemyDeath, gamestate::GameState};
+use crate::{
+    events::{EnemyDeath, ProjectileIntercepted},
+    gamestate::GameState,
+};
 
 use super::combo::ComboCounter;
 
@@ -10,12 +13,17 @@ pub struct ScoreTracker(pub usize);
 pub fn score_system(
     mut score_tracker: ResMut<ScoreTracker>,
     mut enemy_deaths: EventReader<EnemyDeath>,
+    mut intercepts: EventReader<ProjectileIntercepted>,
     combo: Res<ComboCounter>,
 ) {
     for enemy_death in enemy_deaths.read() {
         **score_tracker += (enemy_death.score as f32 * combo.multiplier()) as usize;
         info!("score is now {}", **score_tracker);
     }
+    // shooting down projectiles is a small flat bonus, it doesn't count towards the combo
+    for intercept in intercepts.read() {
+        **score_tracker += intercept.score;
+    }
 }
 
 pub struct ScorePlugin;
@@ -0,0 +1,225 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+// broad phase for collision detection.
+// entities are bucketed into a uniform grid of square cells, every cell their circle overlaps.
+// collision systems query the cells around a bullet or laser instead of testing against every entity.
+// this file only depends on bevy, so that benches/spatial_hash.rs can include it directly.
+
+// a bit larger than the biggest collision radius, so most entities only land in a few cells
+pub const DEFAULT_CELL_SIZE: f32 = 100.0;
+
+#[derive(Copy, Clone, Debug)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub radius: f32,
+    // how far the entry moved over the last physics step, so sweeps can test against its motion as well
+    pub step: Vec2,
+    // lowest cell the entry was inserted into, used to report entries spanning several cells only once
+    first_cell: IVec2,
+}
+
+#[derive(Resource)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<SpatialEntry>>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        SpatialHash::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0);
+        SpatialHash {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    // empties the grid. cells that were used are kept around (without their entries) for one more frame,
+    // so the allocations get reused while the player stays in the same area.
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, entries| {
+            let used = !entries.is_empty();
+            entries.clear();
+            used
+        });
+    }
+
+    // entries cover both where they are and where they were before their last step
+    pub fn insert(&mut self, entity: Entity, position: Vec2, radius: f32, step: Vec2) {
+        let bounds = Rect::from_corners(position - step, position).inflate(radius);
+        let (min, max) = (self.cell(bounds.min), self.cell(bounds.max));
+        let entry = SpatialEntry {
+            entity,
+            position,
+            radius,
+            step,
+            first_cell: min,
+        };
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entry);
+            }
+        }
+    }
+
+    // every entry whose bounding box overlaps the rect, each reported once
+    pub fn query_rect(&self, rect: Rect) -> impl Iterator<Item = &SpatialEntry> {
+        let (min, max) = (self.cell(rect.min), self.cell(rect.max));
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell).map(|entries| (cell, entries)))
+            .flat_map(move |(cell, entries)| {
+                entries.iter().filter(move |entry| {
+                    let bounds = Rect::from_corners(entry.position - entry.step, entry.position)
+                        .inflate(entry.radius);
+                    // entries spanning several cells are only reported from the first cell both boxes share
+                    cell == entry.first_cell.max(min)
+                        && bounds.min.cmple(rect.max).all()
+                        && bounds.max.cmpge(rect.min).all()
+                })
+            })
+    }
+
+    // every entry touched by a circle moving from `start` to `end`, with the fraction of the way along the path
+    // where it first makes contact, sorted by that fraction.
+    // entries move over their own step at the same time, so fast targets can't pass through the circle between frames.
+    pub fn query_sweep(&self, start: Vec2, end: Vec2, radius: f32) -> Vec<(f32, &SpatialEntry)> {
+        let mut hits: Vec<_> = self
+            .query_rect(Rect::from_corners(start, end).inflate(radius))
+            .filter_map(|entry| {
+                // sweep the motion relative to the entry, which starts at `position - step` and ends at `position`
+                sweep_circle(
+                    start + entry.step,
+                    end,
+                    entry.position,
+                    entry.radius + radius,
+                )
+                .map(|time| (time, entry))
+            })
+            .collect();
+        hits.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        hits
+    }
+}
+
+// fraction of the way from `start` to `end` at which a point first comes within `radius` of `center`.
+// 0 if it starts within range, none if it never gets there.
+pub fn sweep_circle(start: Vec2, end: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let offset = start - center;
+    let c = offset.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    // solve |offset + path * t| = radius for the smaller t
+    let path = end - start;
+    let a = path.length_squared();
+    let b = offset.dot(path);
+    if a == 0.0 || b >= 0.0 {
+        // not moving, or moving away
+        return None;
+    }
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let time = (-b - discriminant.sqrt()) / a;
+    (time <= 1.0).then_some(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_circle_hits() {
+        // passes straight through, first touching the circle a quarter of the way along
+        let time = sweep_circle(
+            Vec2::new(-20.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            Vec2::ZERO,
+            10.0,
+        );
+        assert!((time.unwrap() - 0.25).abs() < 1e-5);
+        // starts inside
+        assert_eq!(
+            sweep_circle(Vec2::new(5.0, 0.0), Vec2::new(50.0, 0.0), Vec2::ZERO, 10.0),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn sweep_circle_misses() {
+        // passes by to the side
+        assert_eq!(
+            sweep_circle(
+                Vec2::new(-20.0, 15.0),
+                Vec2::new(20.0, 15.0),
+                Vec2::ZERO,
+                10.0
+            ),
+            None
+        );
+        // stops short
+        assert_eq!(
+            sweep_circle(
+                Vec2::new(-30.0, 0.0),
+                Vec2::new(-15.0, 0.0),
+                Vec2::ZERO,
+                10.0
+            ),
+            None
+        );
+        // moving away
+        assert_eq!(
+            sweep_circle(Vec2::new(15.0, 0.0), Vec2::new(30.0, 0.0), Vec2::ZERO, 10.0),
+            None
+        );
+    }
+
+    #[test]
+    fn query_rect_reports_entries_once() {
+        let mut spatial_hash = SpatialHash::new(10.0);
+        // spans a 5x5 block of cells
+        spatial_hash.insert(Entity::from_raw(0), Vec2::ZERO, 20.0, Vec2::ZERO);
+        spatial_hash.insert(Entity::from_raw(1), Vec2::new(100.0, 0.0), 1.0, Vec2::ZERO);
+        let found: Vec<_> = spatial_hash
+            .query_rect(Rect::new(-50.0, -50.0, 50.0, 50.0))
+            .map(|entry| entry.entity)
+            .collect();
+        assert_eq!(found, vec![Entity::from_raw(0)]);
+        // a query starting partway into the entry's cells still reports it once
+        let found = spatial_hash
+            .query_rect(Rect::new(5.0, 5.0, 200.0, 15.0))
+            .count();
+        assert_eq!(found, 1);
+    }
+
+    #[test]
+    fn query_sweep_catches_moving_targets() {
+        let mut spatial_hash = SpatialHash::new(10.0);
+        // the target crossed the bullet's path this step, ending well clear of it
+        spatial_hash.insert(
+            Entity::from_raw(0),
+            Vec2::new(50.0, 10.0),
+            5.0,
+            Vec2::new(100.0, 0.0),
+        );
+        let hits = spatial_hash.query_sweep(Vec2::new(0.0, 0.0), Vec2::new(0.0, 20.0), 1.0);
+        assert_eq!(hits.len(), 1);
+        // a stationary target at the same place isn't hit
+        let mut spatial_hash = SpatialHash::new(10.0);
+        spatial_hash.insert(Entity::from_raw(0), Vec2::new(50.0, 10.0), 5.0, Vec2::ZERO);
+        let hits = spatial_hash.query_sweep(Vec2::new(0.0, 0.0), Vec2::new(0.0, 20.0), 1.0);
+        assert!(hits.is_empty());
+    }
+}
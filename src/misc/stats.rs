@@ -0,0 +1,153 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::{
+    enemy::EnemyType,
+    events::{
+        EnemyDeath, EnemyHit, PlayerDeath, PlayerDrained, PlayerHit, ProjectileIntercepted,
+        VerticalBound,
+    },
+    mods::guns::WeaponType,
+    reset::{RunReset, RunResetAppExt},
+    userdata::UserData,
+};
+
+use super::{HeatTracker, combo::ComboCounter, hitstun::in_game_or_hitstun, score::ScoreTracker};
+use crate::gamestate::GameState;
+
+#[derive(Default)]
+pub struct WeaponStats {
+    pub damage: f32,
+    pub kills: usize,
+    // hostile projectiles shot down
+    pub intercepts: usize,
+}
+
+// statistics for the current run, shown on the game over screen.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub kills: HashMap<EnemyType, usize>,
+    // damage dealt and kills, by the weapon that dealt them
+    pub weapons: HashMap<WeaponType, WeaponStats>,
+    pub max_combo: usize,
+    pub damage_dealt: f32,
+    pub damage_taken_bullets: f32,
+    pub damage_taken_upper_bound: f32,
+    pub damage_taken_lower_bound: f32,
+    pub time_alive: f32,
+    // the following are only filled in once the run ends
+    pub waves_survived: u32,
+    pub final_score: usize,
+    pub new_high_score: bool,
+    // the weapon that dealt the final blow, if the player was shot down
+    pub killed_by: Option<WeaponType>,
+}
+
+impl RunReset for RunStats {
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+pub fn stats_enemy_death_subscriber(
+    mut stats: ResMut<RunStats>,
+    mut events: EventReader<EnemyDeath>,
+) {
+    for event in events.read() {
+        *stats.kills.entry(event.enemy_type).or_default() += 1;
+        if let Some(source) = event.killed_by {
+            stats.weapons.entry(source.weapon_type).or_default().kills += 1;
+        }
+    }
+}
+
+pub fn stats_enemy_hit_subscriber(mut stats: ResMut<RunStats>, mut events: EventReader<EnemyHit>) {
+    for event in events.read() {
+        stats.damage_dealt += event.damage;
+        stats
+            .weapons
+            .entry(event.source.weapon_type)
+            .or_default()
+            .damage += event.damage;
+    }
+}
+
+pub fn stats_intercept_subscriber(
+    mut stats: ResMut<RunStats>,
+    mut events: EventReader<ProjectileIntercepted>,
+) {
+    for event in events.read() {
+        stats
+            .weapons
+            .entry(event.source.weapon_type)
+            .or_default()
+            .intercepts += 1;
+    }
+}
+
+pub fn stats_player_hit_subscriber(
+    mut stats: ResMut<RunStats>,
+    mut hit_events: EventReader<PlayerHit>,
+    mut drain_events: EventReader<PlayerDrained>,
+) {
+    for event in hit_events.read() {
+        stats.damage_taken_bullets += event.damage;
+    }
+    for event in drain_events.read() {
+        match event.bound {
+            VerticalBound::Upper => stats.damage_taken_upper_bound += event.damage,
+            VerticalBound::Lower => stats.damage_taken_lower_bound += event.damage,
+        }
+    }
+}
+
+pub fn stats_tick_system(time: Res<Time>, mut stats: ResMut<RunStats>, combo: Res<ComboCounter>) {
+    stats.time_alive += time.delta_secs();
+    stats.max_combo = stats.max_combo.max(combo.count);
+}
+
+// records end of run stats, and updates the high score if it was beaten.
+pub fn stats_finalize_system(
+    mut stats: ResMut<RunStats>,
+    mut userdata: ResMut<UserData>,
+    score: Res<ScoreTracker>,
+    heat_tracker: Res<HeatTracker>,
+    mut death_events: EventReader<PlayerDeath>,
+) {
+    if let Some(event) = death_events.read().last() {
+        stats.killed_by = event.killed_by.map(|source| source.weapon_type);
+    }
+    stats.final_score = **score;
+    stats.waves_survived = heat_tracker.spawned_waves();
+    stats.new_high_score = stats.final_score as u32 > userdata.high_score.0;
+
+    if stats.new_high_score {
+        let (weapon, body, engine) = &userdata.selected_build;
+        let build = format!("{weapon:?} / {body:?} / {engine:?}");
+        userdata.high_score = (stats.final_score as u32, build);
+        if let Err(e) = userdata.save() {
+            error!("failed to save new high score: {e}");
+        }
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .reset_on_run_teardown::<RunStats>()
+            .add_systems(
+                Update,
+                (
+                    stats_enemy_death_subscriber,
+                    stats_enemy_hit_subscriber,
+                    stats_intercept_subscriber,
+                    stats_player_hit_subscriber,
+                    stats_tick_system,
+                )
+                    .run_if(in_game_or_hitstun),
+            )
+            .add_systems(OnEnter(GameState::GameEnding), stats_finalize_system);
+    }
+}
@@ -1,7 +1,11 @@
 use bevy::prelude::*;
 
 use crate::{
-    config::GameConfig, mods::engines::SubmarineEngine, physics::Physics, player::TurnAngle,
+    config::GameConfig,
+    events::{PlayerDrained, VerticalBound},
+    mods::engines::SubmarineEngine,
+    physics::Physics,
+    player::{Player, TurnAngle},
 };
 
 use super::HP;
@@ -19,9 +23,11 @@ pub fn vertical_bound_system(
             &mut TurnAngle,
             &Transform,
             Option<&SubmarineEngine>,
+            Has<Player>,
         ),
         With<VerticallyBounded>,
     >,
+    mut drain_events: EventWriter<PlayerDrained>,
     game_config: Res<GameConfig>,
     time: Res<Time>,
 ) {
@@ -29,7 +35,9 @@ pub fn vertical_bound_system(
     let deadzone_width = 0.1; // to calculate from radians, do sin(d/2) where d is the deadzone width in radians.
     // however for small x, sin(x) ~= x
     // thus the deadzone width in radians is approximately 2 times the variable as written.
-    for (_e, mut hp, mut physics, mut turn_angle, transform, maybe_submarine) in query.iter_mut() {
+    for (_e, mut hp, mut physics, mut turn_angle, transform, maybe_submarine, is_player) in
+        query.iter_mut()
+    {
         if transform.translation.y > game_config.upper_bound {
             // handle upper bound
             // should rotate player towards down, and push them down as well
@@ -58,7 +66,14 @@ pub fn vertical_bound_system(
 
             // only drain hp if there's an hp component
             if let Some(hp) = hp.as_mut() {
-                hp.hp -= game_config.upper_hp_drain * time.delta_secs();
+                let damage = game_config.upper_hp_drain * time.delta_secs();
+                hp.hp -= damage;
+                if is_player {
+                    drain_events.write(PlayerDrained {
+                        damage,
+                        bound: VerticalBound::Upper,
+                    });
+                }
             }
         } else if transform.translation.y < game_config.lower_bound {
             // handle lower bound
@@ -87,9 +102,16 @@ pub fn vertical_bound_system(
             physics.velocity.y += game_config.lower_repulsion_strength;
 
             // only drain hp if there's an hp component, and there's no submarine engine component
-            if maybe_submarine.is_none() {
-                if let Some(hp) = hp.as_mut() {
-                    hp.hp -= game_config.lower_hp_drain * time.delta_secs();
+            if maybe_submarine.is_none()
+                && let Some(hp) = hp.as_mut()
+            {
+                let damage = game_config.lower_hp_drain * time.delta_secs();
+                hp.hp -= damage;
+                if is_player {
+                    drain_events.write(PlayerDrained {
+                        damage,
+                        bound: VerticalBound::Lower,
+                    });
                 }
             }
         } else {
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::{config::GameConfig, mods::guns::Bullet, physics::Physics};
+
+// the water surface is at the lower bound.
+// things below it can only be hit by projectiles that are also below it.
+
+// how much projectiles slow down when they hit the water
+const WATER_ENTRY_DRAG: f32 = 0.4;
+// how transparent submerged entities are drawn
+const UNDERWATER_ALPHA: f32 = 0.35;
+
+// marks an entity that can go below the water surface and should be tracked as such
+#[derive(Component)]
+pub struct Submersible;
+
+// added to submersibles and projectiles while they're below the water surface
+#[derive(Component)]
+pub struct Underwater;
+
+pub fn underwater_detection_system(
+    mut commands: Commands,
+    game_config: Res<GameConfig>,
+    query: Query<(Entity, &Transform, Has<Underwater>), With<Submersible>>,
+) {
+    for (entity, transform, is_underwater) in query.iter() {
+        let below = transform.translation.y < game_config.lower_bound;
+        if below && !is_underwater {
+            commands.entity(entity).insert(Underwater);
+        } else if !below && is_underwater {
+            commands.entity(entity).remove::<Underwater>();
+        }
+    }
+}
+
+// projectiles crossing into the water need to penetrate it, spending one piercing charge and losing most of their speed.
+// projectiles that can't pierce splash harmlessly.
+pub fn bullet_water_system(
+    mut commands: Commands,
+    game_config: Res<GameConfig>,
+    mut query: Query<(
+        Entity,
+        &mut Bullet,
+        &mut Physics,
+        &Transform,
+        Has<Underwater>,
+    )>,
+) {
+    for (entity, mut bullet, mut physics, transform, is_underwater) in query.iter_mut() {
+        let below = transform.translation.y < game_config.lower_bound;
+        if below && !is_underwater {
+            if bullet.is_added() {
+                // fired from underwater, so it didn't cross the surface
+                commands.entity(entity).insert(Underwater);
+            } else if bullet.piercing == 0 {
+                commands.entity(entity).despawn();
+            } else {
+                bullet.piercing -= 1;
+                physics.velocity *= WATER_ENTRY_DRAG;
+                commands.entity(entity).insert(Underwater);
+            }
+        } else if !below && is_underwater {
+            commands.entity(entity).remove::<Underwater>();
+        }
+    }
+}
+
+pub fn underwater_visibility_system(
+    query: Query<(&Children, Has<Underwater>), With<Submersible>>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for (children, is_underwater) in query.iter() {
+        let alpha = if is_underwater { UNDERWATER_ALPHA } else { 1.0 };
+        for child in children.iter() {
+            if let Ok(mut sprite) = sprites.get_mut(child) {
+                sprite.color.set_alpha(alpha);
+            }
+        }
+    }
+}
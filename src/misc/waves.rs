@@ -0,0 +1,267 @@
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, reflect::TypePath};
+use rand::random;
+use serde::{Deserialize, Serialize};
+
+use crate::enemy::EnemyType;
+
+use super::{ToVec3, random_in_circle};
+
+// wave archetypes, loaded from waves.ron.
+// each time a wave spawns, one archetype is picked at random (by weight) from those whose wave range covers the current wave.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Formation {
+    // spread randomly over a circle
+    Scattered,
+    // side by side, perpendicular to the direction towards the player
+    Line,
+    // a V shape pointing towards the player
+    Vee,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WaveEntry {
+    pub enemy: EnemyType,
+    pub count: u32,
+    // extra enemies per point of heat above 1
+    #[serde(default)]
+    pub count_per_heat: f32,
+    // chance for each enemy to actually spawn, for rare enemies
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+    // extra chance per point of heat above 1
+    #[serde(default)]
+    pub chance_per_heat: f32,
+}
+
+fn default_chance() -> f32 {
+    1.0
+}
+
+impl WaveEntry {
+    // how many of this enemy to spawn at the given heat
+    pub fn roll_count(&self, heat: f32) -> u32 {
+        let count = self.count + (self.count_per_heat * (heat - 1.0)).max(0.0) as u32;
+        let chance = (self.chance + self.chance_per_heat * (heat - 1.0)).clamp(0.0, 1.0);
+        (0..count).filter(|_| random::<f32>() < chance).count() as u32
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WaveArchetype {
+    pub name: String,
+    // waves this archetype can be picked for, inclusive. no last wave means it stays available forever.
+    pub first_wave: u32,
+    #[serde(default)]
+    pub last_wave: Option<u32>,
+    // relative chance of being picked over other available archetypes
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    // heat tiers this archetype can be picked in, by name. empty means any tier.
+    #[serde(default)]
+    pub tiers: Vec<String>,
+    pub enemies: Vec<WaveEntry>,
+    pub formation: Formation,
+    // how far from the player the formation is centered
+    pub spawn_distance: f32,
+    // how spread out the formation is
+    pub spread: f32,
+    // seconds until the next wave, divided by the current heat tier's spawn rate
+    pub interval: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl WaveArchetype {
+    pub fn available_for(&self, wave: u32, tier: Option<&str>) -> bool {
+        wave >= self.first_wave
+            && self.last_wave.is_none_or(|last| wave <= last)
+            && (self.tiers.is_empty()
+                || tier.is_some_and(|tier| self.tiers.iter().any(|t| t == tier)))
+    }
+
+    // roughly how far from its center a formation of `count` enemies reaches
+    pub fn extent(&self, count: u32) -> f32 {
+        let ranks = count.div_ceil(2) as f32;
+        match self.formation {
+            Formation::Scattered => self.spread,
+            Formation::Line => self.spread * ranks,
+            Formation::Vee => self.spread * ranks * std::f32::consts::SQRT_2,
+        }
+    }
+
+    // positions for `count` enemies, in formation around `center`, facing towards `target`
+    pub fn formation_positions(&self, center: Vec3, target: Vec3, count: u32) -> Vec<Vec3> {
+        let forward = (target - center).truncate().normalize_or(Vec2::Y);
+        let side = forward.perp();
+        (0..count)
+            .map(|i| {
+                // alternate sides, moving further out every other enemy
+                let rank = i.div_ceil(2) as f32;
+                let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+                let offset = match self.formation {
+                    Formation::Scattered => random_in_circle() * self.spread,
+                    Formation::Line => side * sign * rank * self.spread,
+                    Formation::Vee => (side * sign - forward) * rank * self.spread,
+                };
+                center + offset.to_vec3()
+            })
+            .collect()
+    }
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Resource, Clone)]
+pub struct WaveTable {
+    pub archetypes: Vec<WaveArchetype>,
+}
+
+impl WaveTable {
+    // picks a random archetype available for the given wave and heat tier, weighted by each archetype's weight
+    pub fn pick(&self, wave: u32, tier: Option<&str>) -> Option<&WaveArchetype> {
+        let available = self
+            .archetypes
+            .iter()
+            .filter(|archetype| archetype.available_for(wave, tier) && archetype.weight > 0.0);
+        let total_weight: f32 = available.clone().map(|archetype| archetype.weight).sum();
+        let mut roll = random::<f32>() * total_weight;
+        let mut last = None;
+        for archetype in available {
+            if roll < archetype.weight {
+                return Some(archetype);
+            }
+            roll -= archetype.weight;
+            last = Some(archetype);
+        }
+        // float error can leave a tiny bit of roll over
+        last
+    }
+}
+
+// direction to center a wave in, relative to the player
+pub fn random_spawn_direction() -> Vec3 {
+    Vec2::from_angle(random::<f32>() * TAU).to_vec3()
+}
+
+impl Default for WaveTable {
+    fn default() -> Self {
+        let entry = |enemy, count| WaveEntry {
+            enemy,
+            count,
+            count_per_heat: 0.0,
+            chance: 1.0,
+            chance_per_heat: 0.0,
+        };
+        // aces are rare, but show up more often the hotter things get
+        let rare_ace = WaveEntry {
+            chance: 0.05,
+            chance_per_heat: 0.05,
+            ..entry(EnemyType::Ace, 1)
+        };
+        WaveTable {
+            archetypes: vec![
+                WaveArchetype {
+                    name: "opening".to_string(),
+                    first_wave: 0,
+                    last_wave: Some(2),
+                    weight: 1.0,
+                    tiers: vec![],
+                    enemies: vec![WaveEntry {
+                        count_per_heat: 1.0,
+                        ..entry(EnemyType::Basic, 10)
+                    }],
+                    formation: Formation::Scattered,
+                    spawn_distance: 400.0,
+                    spread: 250.0,
+                    interval: 60.0,
+                },
+                WaveArchetype {
+                    name: "mixed".to_string(),
+                    first_wave: 3,
+                    last_wave: Some(5),
+                    weight: 1.0,
+                    tiers: vec![],
+                    enemies: vec![
+                        WaveEntry {
+                            count_per_heat: 1.0,
+                            ..entry(EnemyType::Basic, 8)
+                        },
+                        entry(EnemyType::Boat, 2),
+                        rare_ace.clone(),
+                    ],
+                    formation: Formation::Scattered,
+                    spawn_distance: 450.0,
+                    spread: 250.0,
+                    interval: 60.0,
+                },
+                WaveArchetype {
+                    name: "squadron".to_string(),
+                    first_wave: 4,
+                    last_wave: None,
+                    weight: 0.5,
+                    tiers: ["Warm", "Hot", "Blazing", "Inferno"]
+                        .map(String::from)
+                        .to_vec(),
+                    enemies: vec![entry(EnemyType::Ace, 1), entry(EnemyType::Basic, 6)],
+                    formation: Formation::Vee,
+                    spawn_distance: 600.0,
+                    spread: 50.0,
+                    interval: 45.0,
+                },
+                WaveArchetype {
+                    name: "fleet".to_string(),
+                    first_wave: 6,
+                    last_wave: None,
+                    weight: 1.0,
+                    tiers: vec![],
+                    enemies: vec![
+                        WaveEntry {
+                            count_per_heat: 1.0,
+                            ..entry(EnemyType::Basic, 8)
+                        },
+                        entry(EnemyType::Boat, 2),
+                        entry(EnemyType::Destroyer, 1),
+                        entry(EnemyType::Submarine, 1),
+                        WaveEntry {
+                            chance: 0.5,
+                            chance_per_heat: 0.1,
+                            ..entry(EnemyType::Lancer, 1)
+                        },
+                        rare_ace,
+                    ],
+                    formation: Formation::Scattered,
+                    spawn_distance: 500.0,
+                    spread: 300.0,
+                    interval: 60.0,
+                },
+                WaveArchetype {
+                    name: "wolfpack".to_string(),
+                    first_wave: 8,
+                    last_wave: None,
+                    weight: 0.5,
+                    tiers: ["Hot", "Blazing", "Inferno"].map(String::from).to_vec(),
+                    enemies: vec![entry(EnemyType::Submarine, 3), entry(EnemyType::Basic, 4)],
+                    formation: Formation::Line,
+                    spawn_distance: 500.0,
+                    spread: 120.0,
+                    interval: 50.0,
+                },
+                WaveArchetype {
+                    name: "lancers".to_string(),
+                    first_wave: 5,
+                    last_wave: None,
+                    weight: 0.5,
+                    tiers: ["Hot", "Blazing", "Inferno"].map(String::from).to_vec(),
+                    enemies: vec![entry(EnemyType::Lancer, 2), entry(EnemyType::Basic, 4)],
+                    formation: Formation::Line,
+                    spawn_distance: 550.0,
+                    spread: 100.0,
+                    interval: 50.0,
+                },
+            ],
+        }
+    }
+}
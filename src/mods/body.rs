@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use super::Recalculated;
 
 #[allow(dead_code)]
-#[derive(Default, Serialize, Deserialize, Clone)]
+#[derive(Default, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub enum BodyType {
     #[default]
     Normal,
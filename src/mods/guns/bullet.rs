@@ -2,16 +2,22 @@ use bevy::prelude::*;
 
 use crate::{
     enemy::Enemy,
-    events::{EnemyHit, PlayerHit},
-    misc::{CollisionRadius, HP},
+    events::{DamageSource, EnemyHit, PlayerHit, ProjectileIntercepted},
+    misc::{CollisionRadius, HP, SpatialHash, water::Underwater},
+    physics::Physics,
     player::Player,
 };
 
+use super::Interceptable;
+
 #[derive(Component)]
 pub struct Bullet {
     pub damage: f32,
     pub piercing: u32,
     pub hostile_to_player: bool,
+    // everything this bullet has already hit, so piercing bullets don't hit the same thing again while passing through it
+    pub hits: Vec<Entity>,
+    pub source: DamageSource,
 }
 
 // pub fn damage_calculator(v1: Vec3, v2: Vec3, m: f32) -> f32 {
@@ -24,20 +30,53 @@ pub struct Bullet {
 pub fn player_bullet_collision_system(
     mut commands: Commands,
     mut hit_events: EventWriter<PlayerHit>,
-    mut query1: Query<(&mut HP, &Transform, &CollisionRadius), With<Player>>,
-    mut query2: Query<(Entity, &mut Bullet, &Transform, &CollisionRadius)>,
+    mut query1: Query<(Entity, &mut HP), With<Player>>,
+    mut query2: Query<(
+        Entity,
+        &mut Bullet,
+        &Transform,
+        &Physics,
+        &CollisionRadius,
+        Option<&Interceptable>,
+    )>,
+    spatial_hash: Res<SpatialHash>,
     // debug_timer: Res<DebugTimer>,
 ) -> Result<(), BevyError> {
-    let (mut hp, player_tx, &player_collision_radius) = query1.single_mut()?;
-    for (bullet_entity, mut bullet, bullet_tx, &bullet_collision_radius) in query2.iter_mut() {
-        if !bullet.hostile_to_player {
-            // skip because bullet is not hostile to player
+    let (player_entity, mut hp) = query1.single_mut()?;
+    for (
+        bullet_entity,
+        mut bullet,
+        bullet_tx,
+        bullet_physics,
+        &bullet_collision_radius,
+        interceptable,
+    ) in query2.iter_mut()
+    {
+        if !bullet.hostile_to_player || bullet.hits.contains(&player_entity) {
+            // skip because bullet is not hostile to player, or already went through them
+            continue;
+        }
+        if interceptable.is_some_and(|interceptable| interceptable.hp <= 0.0) {
+            // shot down this frame
             continue;
         }
-        let length_squared =
-            (player_tx.translation.truncate() - bullet_tx.translation.truncate()).length_squared();
-        if length_squared < (*player_collision_radius + *bullet_collision_radius).powi(2) {
-            hit_events.write_default();
+        let previous = bullet_physics.previous_translation(bullet_tx);
+        let hit = spatial_hash
+            .query_sweep(
+                previous.truncate(),
+                bullet_tx.translation.truncate(),
+                *bullet_collision_radius,
+            )
+            .into_iter()
+            .find(|(_, entry)| entry.entity == player_entity);
+        if let Some((time, _)) = hit {
+            bullet.hits.push(player_entity);
+            hit_events.write(PlayerHit {
+                damage: bullet.damage,
+                source: bullet.source,
+                position: previous.lerp(bullet_tx.translation, time),
+                sustained: false,
+            });
 
             hp.hp -= bullet.damage;
             info!("player hp is now {}", hp.hp);
@@ -52,35 +91,94 @@ pub fn player_bullet_collision_system(
     Ok(())
 }
 
+// friendly bullets against enemies, and against hostile projectiles that can be shot down.
+// both are handled in one sweep, so piercing is spent in the order the bullet reaches things.
 pub fn enemy_bullet_collision_system(
     mut commands: Commands,
     mut hit_events: EventWriter<EnemyHit>,
-    mut query1: Query<(Entity, &mut HP, &Transform, &CollisionRadius), With<Enemy>>,
-    mut query2: Query<(Entity, &mut Bullet, &Transform, &CollisionRadius)>,
+    mut intercept_events: EventWriter<ProjectileIntercepted>,
+    // enemies can be parts of other enemies (i.e. turrets), so use global transforms
+    mut query1: Query<(&mut HP, Has<Underwater>), With<Enemy>>,
+    mut interceptables: Query<(&mut Interceptable, Has<Underwater>)>,
+    mut query2: Query<(
+        Entity,
+        &mut Bullet,
+        &Transform,
+        &Physics,
+        &CollisionRadius,
+        Has<Underwater>,
+    )>,
+    spatial_hash: Res<SpatialHash>,
 ) {
-    for (enemy_entity, mut hp, enemy_tx, &enemy_collision_radius) in query1.iter_mut() {
-        for (bullet_entity, mut bullet, bullet_tx, &bullet_collision_radius) in query2.iter_mut() {
-            if bullet.hostile_to_player {
-                // skip because bullet is hostile to player and thus not hostile to enemies
+    for (
+        bullet_entity,
+        mut bullet,
+        bullet_tx,
+        bullet_physics,
+        &bullet_collision_radius,
+        bullet_underwater,
+    ) in query2.iter_mut()
+    {
+        if bullet.hostile_to_player {
+            // skip because bullet is hostile to player and thus not hostile to enemies
+            continue;
+        }
+        // sweep along the path the bullet took this frame, so fast bullets can't skip over enemies at low frame rates.
+        // hits come back in the order the bullet reached them.
+        let previous = bullet_physics.previous_translation(bullet_tx);
+        let hits = spatial_hash.query_sweep(
+            previous.truncate(),
+            bullet_tx.translation.truncate(),
+            *bullet_collision_radius,
+        );
+        for (time, entry) in hits {
+            let enemy_entity = entry.entity;
+            if bullet.hits.contains(&enemy_entity) {
                 continue;
             }
-            let length_squared = (enemy_tx.translation.truncate()
-                - bullet_tx.translation.truncate())
-            .length_squared();
-
-            if length_squared < (*bullet_collision_radius + *enemy_collision_radius).powi(2) {
+            let position = previous.lerp(bullet_tx.translation, time);
+            if let Ok((mut hp, enemy_underwater)) = query1.get_mut(enemy_entity) {
+                if bullet_underwater != enemy_underwater {
+                    // the water surface separates them
+                    continue;
+                }
+                bullet.hits.push(enemy_entity);
                 hit_events.write(EnemyHit {
                     entity: enemy_entity,
                     damage: bullet.damage,
+                    source: bullet.source,
+                    position,
                 });
                 hp.hp -= bullet.damage;
                 info!("enemy hp is now {}", hp.hp);
-                if bullet.piercing == 0 {
-                    // QUESTION: consider whether this should be handled as an event. i.e. fire a BulletDestroyed event so that some fx and a sound can be played.
-                    commands.entity(bullet_entity).despawn();
-                } else {
-                    bullet.piercing -= 1;
+            } else if let Ok((mut interceptable, projectile_underwater)) =
+                interceptables.get_mut(enemy_entity)
+            {
+                if bullet_underwater != projectile_underwater || interceptable.hp <= 0.0 {
+                    // separated by the water surface, or already shot down this frame
+                    continue;
+                }
+                bullet.hits.push(enemy_entity);
+                interceptable.hp -= bullet.damage;
+                if interceptable.hp <= 0.0 {
+                    intercept_events.write(ProjectileIntercepted {
+                        position,
+                        source: bullet.source,
+                        score: interceptable.score,
+                    });
+                    commands.entity(enemy_entity).try_despawn();
                 }
+            } else {
+                // not something bullets can hit
+                continue;
+            }
+            if bullet.piercing == 0 {
+                // QUESTION: consider whether this should be handled as an event. i.e. fire a BulletDestroyed event so that some fx and a sound can be played.
+                commands.entity(bullet_entity).despawn();
+                // bullet is spent, don't let it hit anything else this frame
+                break;
+            } else {
+                bullet.piercing -= 1;
             }
         }
     }
@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+// opt-in for slow hostile projectiles that the player can shoot down.
+// interceptable projectiles are put into the spatial hash, so friendly bullets find them in the same sweep as enemies.
+// see enemy_bullet_collision_system.
+#[derive(Component, Copy, Clone)]
+pub struct Interceptable {
+    pub hp: f32,
+    // bonus score for shooting it down
+    pub score: usize,
+}
+
+impl Interceptable {
+    pub fn new(hp: f32, score: usize) -> Self {
+        Interceptable { hp, score }
+    }
+}
@@ -1,83 +1,284 @@
-use std::f32::consts::PI;
+use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{color::palettes::css::RED, platform::collections::HashSet, prelude::*, sprite::Anchor};
 
 use crate::{
     enemy::Enemy,
-    events::EnemyHit,
-    misc::{CollisionRadius, HP},
+    events::{DamageSource, EnemyHit, PlayerHit, WeaponFired},
+    input::Intent,
+    misc::{HP, SpatialHash, water::Underwater},
+    physics::Physics,
+    player::Player,
 };
 
+use super::{WeaponData, WeaponSubtype};
+
+// lasers are continuous beams. a new laser entity is spawned every time the weapon fires and lives for a couple of frames,
+// so a held trigger keeps the beam up. damage is per second and gets applied every tick the beam touches something.
 #[derive(Component)]
 pub struct Laser {
+    // damage per second
     pub damage: f32,
     pub hostile: bool,
     pub width: f32,
     pub max_dist: f32,
+    // how many targets the beam passes through before it's stopped
+    pub piercing: u32,
+    // overlapping lasers from the same source entity count as one beam
+    pub source: DamageSource,
+    // how far the beam currently reaches, either max_dist or wherever it was stopped
+    pub length: f32,
 }
 
 impl Laser {
-    pub fn new(damage: f32, hostile: bool, width: f32, max_dist: f32) -> Self {
+    pub fn new(
+        damage: f32,
+        hostile: bool,
+        width: f32,
+        max_dist: f32,
+        piercing: u32,
+        source: DamageSource,
+    ) -> Self {
         assert!(width > 0.0);
+        assert!(max_dist.is_finite());
         Laser {
             damage,
             hostile,
             width,
             max_dist,
+            piercing,
+            source,
+            length: max_dist,
         }
     }
 }
 
-pub fn enemy_laser_collision_system(
-    mut enemies: Query<(Entity, &mut HP, &CollisionRadius, &Transform), With<Enemy>>,
-    mut hit_events: EventWriter<EnemyHit>,
-    lasers: Query<(&Laser, &GlobalTransform)>,
+pub fn laser_collision_system(
+    time: Res<Time>,
+    // lasers don't reach underwater enemies
+    mut enemies: Query<&mut HP, (With<Enemy>, Without<Underwater>, Without<Player>)>,
+    mut player: Query<(Entity, &mut HP), (With<Player>, Without<Enemy>)>,
+    mut enemy_hit_events: EventWriter<EnemyHit>,
+    mut player_hit_events: EventWriter<PlayerHit>,
+    mut lasers: Query<(&mut Laser, &GlobalTransform)>,
+    spatial_hash: Res<SpatialHash>,
+    // (source, target) pairs that were hit last tick
+    mut previous_hits: Local<HashSet<(Entity, Entity)>>,
 ) {
-    // no acceleration structure, complexity is O(n*m) where n is laser count and m is enemy count
-    // in most cases, laser count will just be one, so there's no need to worry about complexity
-
-    // in the future if multiplayer is implemented, we'll need enemy deaths to be attributed to player sources
-    // to properly allocate scores
-
-    for (laser, transform) in lasers.iter() {
-        // let entity = commands.get_entity(entity)
+    let mut hits = HashSet::new();
+    let mut player = player.single_mut().ok();
 
+    for (mut laser, transform) in lasers.iter_mut() {
         let (_, rotation, translation) = transform.to_scale_rotation_translation();
         let laser_origin = translation.truncate();
-        // let laser_origin = transform.translation().truncate();
-        // let comparison_vec = Vec3::new(1.0, 0.0, 0.0);
-        // let angle
-        // let cos = (transform.rotation * comparison_vec ).dot(comparison_vec);
-        // let sin = .length_squared().sqrt();
-        // info!("{:?}", axis);
-        // let (dy, dx) = (angle + PI/2.0).sin_cos();
-        let (_, angle) = rotation.to_axis_angle();
-        // info!("{:?} {:?}", axis, angle);
-        let (dy, dx) = (angle + PI / 2.0).sin_cos();
-        let direction = Vec2::new(dx, dy);
-
-        // need to determine if the laser overlaps with the enemies' hitbox
-        // use circle hitboxes to begin with
-
-        for (enemy_entity, mut hp, &enemy_radius, enemy_pos) in enemies.iter_mut() {
-            let enemy_pos = enemy_pos.translation.truncate();
-
-            let v = enemy_pos - laser_origin;
-            let cos = v.normalize().dot(direction);
-            if cos < 0.0 {
-                // only allow laser damage in front of the laser
+        let direction = (rotation * Vec3::Y).truncate();
+
+        // the spatial hash narrows targets down to those near the beam's bounding box.
+        // then find how far along the beam each target gets hit, using circle hitboxes.
+        let laser_end = laser_origin + direction * laser.max_dist;
+        let bounds = Rect::from_corners(laser_origin, laser_end).inflate(laser.width);
+        let mut targets: Vec<(f32, Entity)> = spatial_hash
+            .query_rect(bounds)
+            .filter(|entry| {
+                if laser.hostile {
+                    player
+                        .as_ref()
+                        .is_some_and(|(player_entity, _)| *player_entity == entry.entity)
+                } else {
+                    enemies.contains(entry.entity)
+                }
+            })
+            .filter_map(|entry| {
+                let v = entry.position - laser_origin;
+                let along = v.dot(direction);
+                let across = (v - direction * along).length();
+                let reach = laser.width + entry.radius;
+                if along < 0.0 || along > laser.max_dist || across > reach {
+                    // only allow laser damage in front of the laser, and within its range
+                    return None;
+                }
+                // where the beam meets the edge of the hitbox
+                let distance = (along - (reach * reach - across * across).sqrt()).max(0.0);
+                Some((distance, entry.entity))
+            })
+            .collect();
+        targets.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        // the beam stops at the last target it can pierce
+        let max_targets = laser.piercing as usize + 1;
+        laser.length = if targets.len() >= max_targets {
+            targets[max_targets - 1].0
+        } else {
+            laser.max_dist
+        };
+        targets.truncate(max_targets);
+
+        let damage = laser.damage * time.delta_secs();
+        for (distance, target) in targets {
+            let position = (laser_origin + direction * distance).extend(translation.z);
+            if !hits.insert((laser.source.entity, target)) {
+                // already hit by another laser from the same source this tick
                 continue;
             }
-            let proj = v.project_onto(direction);
-            let rej = v - proj;
-            if rej.length_squared() < (laser.width + *enemy_radius).powi(2)
-                && proj.length_squared() < laser.max_dist * laser.max_dist
-            {
-                hit_events.write(EnemyHit {
-                    entity: enemy_entity,
-                    damage: laser.damage,
+            if let Ok(mut hp) = enemies.get_mut(target) {
+                enemy_hit_events.write(EnemyHit {
+                    entity: target,
+                    damage,
+                    source: laser.source,
+                    position,
+                });
+                hp.hp -= damage;
+            } else if let Some((_, hp)) = player.as_mut() {
+                player_hit_events.write(PlayerHit {
+                    damage,
+                    source: laser.source,
+                    position,
+                    sustained: previous_hits.contains(&(laser.source.entity, target)),
                 });
-                hp.hp -= laser.damage;
+                hp.hp -= damage;
+            }
+        }
+    }
+    *previous_hits = hits;
+}
+
+// stretches the beam sprite to however far the laser reaches
+pub fn laser_beam_visual_system(
+    lasers: Query<(&Laser, &Children), Changed<Laser>>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for (laser, children) in lasers.iter() {
+        for child in children.iter() {
+            if let Ok(mut sprite) = sprites.get_mut(child) {
+                sprite.custom_size = Some(Vec2::new(laser.width * 2.0, laser.length));
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum BeamPhase {
+    Ready,
+    Charging,
+    Firing,
+    Cooldown,
+}
+
+// enemy beam weapons telegraph their shots. once the enemy decides to fire, a warning line shows where the beam will go
+// for `charge_time`, then the beam is held for `beam_time` whether or not the enemy still wants to fire, then it cools down.
+#[derive(Component)]
+pub struct BeamCharge {
+    pub charge_time: Duration,
+    pub beam_time: Duration,
+    pub cooldown: Duration,
+    phase: BeamPhase,
+    timer: Timer,
+    warning: Option<Entity>,
+}
+
+impl BeamCharge {
+    pub fn new(charge_time: Duration, beam_time: Duration, cooldown: Duration) -> Self {
+        BeamCharge {
+            charge_time,
+            beam_time,
+            cooldown,
+            phase: BeamPhase::Ready,
+            timer: Timer::default(),
+            warning: None,
+        }
+    }
+
+    fn enter(&mut self, phase: BeamPhase, duration: Duration) {
+        self.phase = phase;
+        self.timer = Timer::new(duration, TimerMode::Once);
+    }
+}
+
+// the line shown while a beam weapon charges
+#[derive(Component)]
+pub struct BeamWarning;
+
+// how visible the warning line is at the start and end of the charge
+const WARNING_ALPHA: (f32, f32) = (0.15, 0.8);
+
+// fires enemy beam weapons, in place of enemy_gun_system
+pub fn beam_charge_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut BeamCharge, &mut WeaponData, &Physics, &Intent), With<Enemy>>,
+    mut warnings: Query<&mut Sprite, With<BeamWarning>>,
+    mut event_writer: EventWriter<WeaponFired>,
+) {
+    for (entity, mut charge, mut weapon, physics, intent) in query.iter_mut() {
+        let WeaponSubtype::Laser { max_dist, .. } = weapon.subtype else {
+            continue;
+        };
+        charge.timer.tick(time.delta());
+        match charge.phase {
+            BeamPhase::Ready => {
+                if intent.fire {
+                    let charge_time = charge.charge_time;
+                    charge.enter(BeamPhase::Charging, charge_time);
+                    let warning = commands
+                        .spawn((
+                            BeamWarning,
+                            Sprite {
+                                image: weapon.sprite_handle.clone(),
+                                color: RED.with_alpha(WARNING_ALPHA.0).into(),
+                                custom_size: Some(Vec2::new(2.0, max_dist)),
+                                anchor: Anchor::BottomCenter,
+                                ..Default::default()
+                            },
+                            // above the enemy's own sprite
+                            Transform::from_translation(Vec3::new(0.0, 0.0, 2.0)),
+                            Visibility::Visible,
+                            ChildOf(entity),
+                        ))
+                        .id();
+                    charge.warning = Some(warning);
+                }
+            }
+            BeamPhase::Charging => {
+                if let Some(mut sprite) = charge.warning.and_then(|w| warnings.get_mut(w).ok()) {
+                    let alpha = WARNING_ALPHA.0
+                        + (WARNING_ALPHA.1 - WARNING_ALPHA.0) * charge.timer.fraction();
+                    sprite.color.set_alpha(alpha);
+                }
+                if charge.timer.finished() {
+                    if let Some(warning) = charge.warning.take() {
+                        commands.entity(warning).try_despawn();
+                    }
+                    let beam_time = charge.beam_time;
+                    charge.enter(BeamPhase::Firing, beam_time);
+                    // the beam comes up as soon as the charge is done
+                    event_writer.write(WeaponFired::new(
+                        entity,
+                        physics.velocity,
+                        true,
+                        weapon.weapon_type,
+                    ));
+                    weapon.timer.reset();
+                }
+            }
+            BeamPhase::Firing => {
+                if weapon.timer.tick(time.delta()).finished() {
+                    event_writer.write(WeaponFired::new(
+                        entity,
+                        physics.velocity,
+                        true,
+                        weapon.weapon_type,
+                    ));
+                    weapon.timer.reset();
+                }
+                if charge.timer.finished() {
+                    let cooldown = charge.cooldown;
+                    charge.enter(BeamPhase::Cooldown, cooldown);
+                }
+            }
+            BeamPhase::Cooldown => {
+                if charge.timer.finished() {
+                    charge.phase = BeamPhase::Ready;
+                }
             }
         }
     }
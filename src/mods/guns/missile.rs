@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+use crate::{enemy::Enemy, misc::water::Underwater, physics::Physics, player::Player};
+
+use super::Bullet;
+
+// turns a bullet into a homing missile.
+// hostile missiles home in on the player, friendly missiles home in on the nearest enemy above the water.
+#[derive(Component)]
+pub struct Homing {
+    // radians per second
+    pub turn_rate: f32,
+}
+
+impl Default for Homing {
+    fn default() -> Self {
+        Homing { turn_rate: 2.0 }
+    }
+}
+
+pub fn homing_system(
+    time: Res<Time>,
+    mut missiles: Query<(&mut Physics, &Transform, &Bullet, &Homing)>,
+    player: Query<&Transform, With<Player>>,
+    enemies: Query<&GlobalTransform, (With<Enemy>, Without<Underwater>)>,
+) {
+    let player_position = player.single().ok().map(|transform| transform.translation);
+    for (mut physics, transform, bullet, homing) in missiles.iter_mut() {
+        let position = transform.translation;
+        let target = if bullet.hostile_to_player {
+            player_position
+        } else {
+            enemies
+                .iter()
+                .map(|enemy| enemy.translation())
+                .min_by(|a, b| {
+                    a.distance_squared(position)
+                        .total_cmp(&b.distance_squared(position))
+                })
+        };
+        let Some(target) = target else {
+            continue;
+        };
+
+        // rotate the velocity towards the target, keeping the speed the same
+        let velocity = physics.velocity.truncate();
+        let to_target = (target - position).truncate();
+        if velocity.length_squared() == 0.0 || to_target.length_squared() == 0.0 {
+            continue;
+        }
+        let max_step = homing.turn_rate * time.delta_secs();
+        let angle = velocity.angle_to(to_target).clamp(-max_step, max_step);
+        physics.velocity = Vec2::from_angle(angle)
+            .rotate(velocity)
+            .extend(physics.velocity.z);
+    }
+}
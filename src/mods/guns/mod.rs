@@ -1,22 +1,30 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
 use serde::{Deserialize, Serialize};
 
 pub mod bullet;
+pub mod intercept;
 pub mod laser;
+pub mod missile;
 
 use crate::{
-    enemy::Enemy,
-    events::WeaponFired,
+    config::GameConfig,
+    enemy::{Enemy, Turret},
+    events::{DamageSource, DamageType, WeaponFired},
     input::Intent,
-    misc::{CollisionRadius, Lifetime},
-    physics::Physics,
+    misc::{CollisionRadius, HeatTracker, Lifetime, in_game_no_hitstun, spatial_hash_system},
+    physics::{Physics, linear_physics},
     player::Player,
+    reset::RunScoped,
 };
 
 pub use bullet::{Bullet, enemy_bullet_collision_system, player_bullet_collision_system};
-pub use laser::{Laser, enemy_laser_collision_system};
+pub use intercept::Interceptable;
+pub use laser::{
+    BeamCharge, Laser, beam_charge_system, laser_beam_visual_system, laser_collision_system,
+};
+pub use missile::{Homing, homing_system};
 
 use crate::gamestate::GameState;
 
@@ -27,11 +35,21 @@ impl Plugin for WeaponSubsystemPlugin {
         app.add_systems(
             Update,
             (
-                player_bullet_collision_system,
-                enemy_bullet_collision_system,
-                enemy_laser_collision_system,
+                // the spatial hash is rebuilt once per frame, before anything queries it
+                spatial_hash_system,
+                // projectiles shot down by player bullets are gone before they get a chance to hit the player
+                (
+                    enemy_bullet_collision_system,
+                    player_bullet_collision_system,
+                )
+                    .chain(),
+                laser_collision_system,
+                laser_beam_visual_system,
             )
-                .run_if(in_state(GameState::InGame)),
+                .chain()
+                // bullets are swept along the path they moved this frame, so this has to run after they've moved
+                .after(linear_physics)
+                .run_if(in_game_no_hitstun),
         );
     }
 }
@@ -63,6 +81,8 @@ pub struct WeaponData {
     pub piercing: u32, // QUESTION: maybe change this f32 to represent some chance to pierce? i.e. 50% chance to pierce for each target hit.
     // note, we're tracking player hostility on the bullets, not on the gun. enemy-spawned bullets are hostile to the player, player-spawned bullets are not.
     pub lifetime: Duration,
+    // hostile projectiles from this weapon can be shot down
+    pub interceptable: Option<Interceptable>,
     // TODO: think about whether this game will ever have 2 player vs or co-op.
     // if there's VS, then player hostility would need to be reworked to just reference the original entity and make sure collisions are ignored when they involve the bullet hitting the original entity.
     pub subtype: WeaponSubtype,
@@ -94,6 +114,7 @@ impl WeaponData {
             spread: bullet_spread,
             lifetime: bullet_lifetime,
             piercing,
+            interceptable: None,
             subtype: WeaponSubtype::BulletBased {
                 bullet_scale,
                 velocity: bullet_velocity,
@@ -125,13 +146,14 @@ impl WeaponData {
             damage,
             spread,
             lifetime,
+            interceptable: None,
             subtype: WeaponSubtype::Laser { width, max_dist },
         }
     }
 }
 
 #[allow(dead_code)]
-#[derive(Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default, Serialize, Deserialize)]
 pub enum WeaponType {
     #[default]
     MachineGun,
@@ -145,6 +167,13 @@ pub enum WeaponType {
 }
 
 impl WeaponType {
+    pub fn damage_type(self) -> DamageType {
+        match self {
+            WeaponType::Missile => DamageType::Explosive,
+            WeaponType::Laser => DamageType::Energy,
+            _ => DamageType::Kinetic,
+        }
+    }
     pub fn data_from_type_and_handle(self, handle: Handle<Image>) -> WeaponData {
         match self {
             WeaponType::SlugGun => WeaponData::new_bullet_subtype(
@@ -200,14 +229,33 @@ impl WeaponType {
                 self,
                 Duration::from_millis(10),
                 true,
-                5.0,
+                300.0, // per second
                 0.0,
                 Duration::from_millis(20),
                 15.0,
-                f32::INFINITY,
+                1000.0,
                 5,
             ),
-            WeaponType::Missile => todo!(),
+            WeaponType::Missile => WeaponData {
+                // slow enough to be shot down, but takes a few hits
+                interceptable: Some(Interceptable::new(30.0, 10)),
+                ..WeaponData::new_bullet_subtype(
+                    handle,
+                    self,
+                    Duration::from_millis(800),
+                    true,
+                    25.0,
+                    0.4,
+                    Vec3::new(0.0, 300.0, 0.0),
+                    Vec3::ZERO, // no gravity, so that homing doesn't have to fight it
+                    1.0,
+                    Duration::from_millis(5000),
+                    1.2,
+                    1,
+                    0.01,
+                    0,
+                )
+            },
             WeaponType::SpreadGun => WeaponData::new_bullet_subtype(
                 handle,
                 self,
@@ -231,7 +279,15 @@ impl WeaponType {
 fn gun_fire_system(
     mut commands: Commands,
     mut event_reader: EventReader<WeaponFired>,
-    query: Query<(Entity, &Transform, &WeaponData)>,
+    query: Query<(
+        Entity,
+        &Transform,
+        &GlobalTransform,
+        &WeaponData,
+        Has<ChildOf>,
+    )>,
+    heat_tracker: Res<HeatTracker>,
+    game_config: Res<GameConfig>,
     // asset_server: Res<AssetServer>,
 ) {
     if query.is_empty() {
@@ -245,13 +301,32 @@ fn gun_fire_system(
         // get entity properties for the owner of the gun that was fired
         // for example a triplicate gun would fire groups of 3 bullets with spread, and a shotgun would fire a spread of bullets randomly.
 
-        let Ok((_e /*, physics */, transform, weapon)) = query.get(event.entity) else {
+        let Ok((_e /*, physics */, transform, global_transform, weapon, is_child)) =
+            query.get(event.entity)
+        else {
             continue;
         };
+        // weapons mounted on child entities (i.e. turrets) need to fire from their world space position
+        let transform = if is_child {
+            global_transform.compute_transform()
+        } else {
+            *transform
+        };
 
         assert!(event.weapon_type == weapon.weapon_type);
 
         let clean_transform = Transform::from_translation(transform.translation);
+        let source = DamageSource {
+            entity: event.entity,
+            weapon_type: weapon.weapon_type,
+            damage_type: weapon.weapon_type.damage_type(),
+        };
+        // enemy weapons hit harder the hotter things get
+        let damage = if event.hostile {
+            weapon.damage * heat_tracker.damage_multiplier(&game_config)
+        } else {
+            weapon.damage
+        };
 
         match weapon.subtype {
             WeaponSubtype::BulletBased {
@@ -265,62 +340,80 @@ fn gun_fire_system(
                 // fire a single batch per event, based on the num_spawned_per_shot
                 for _ in 0..num_spawned_per_shot {
                     let angle = weapon.spread * (rand::random::<f32>() - 0.5);
-                    commands
-                        .spawn((
-                            clean_transform,
-                            Bullet {
-                                damage: weapon.damage,
-                                piercing: weapon.piercing,
-                                hostile_to_player: event.hostile,
+                    let mut bullet = commands.spawn((
+                        clean_transform,
+                        RunScoped,
+                        Bullet {
+                            damage,
+                            piercing: weapon.piercing,
+                            hostile_to_player: event.hostile,
+                            hits: Vec::new(),
+                            source,
+                        },
+                        CollisionRadius(bullet_scale * 10.0),
+                        Lifetime::new(weapon.lifetime),
+                        Physics {
+                            mass: bullet_mass,
+                            velocity: event.entity_velocity
+                                + transform.rotation.mul_quat(Quat::from_rotation_z(angle))
+                                    * velocity,
+                            gravity,
+                            friction,
+                            ..Default::default()
+                        },
+                        Visibility::Visible,
+                    ));
+                    bullet.insert_if(Homing::default(), || {
+                        weapon.weapon_type == WeaponType::Missile
+                    });
+                    if let Some(interceptable) = weapon.interceptable.filter(|_| event.hostile) {
+                        bullet.insert(interceptable);
+                    }
+                    bullet.with_children(|child_builder| {
+                        // scale down bullet. this is because many bullets of different sizes will share the same sprite.
+                        child_builder.spawn((
+                            Sprite {
+                                image: weapon.sprite_handle.clone(),
+                                ..Default::default()
                             },
-                            CollisionRadius(bullet_scale * 10.0),
-                            Lifetime::new(weapon.lifetime),
-                            Physics {
-                                mass: bullet_mass,
-                                velocity: event.entity_velocity
-                                    + transform.rotation.mul_quat(Quat::from_rotation_z(angle))
-                                        * velocity,
-                                gravity,
-                                friction,
+                            Transform {
+                                scale: Vec3::splat(bullet_scale),
+                                translation: Vec3::new(0.0, 0.0, 1.0), // change Z for sprite so that this draws above the background
+                                ..Default::default()
                             },
                             Visibility::Visible,
-                        ))
-                        .with_children(|child_builder| {
-                            // scale down bullet. this is because many bullets of different sizes will share the same sprite.
-                            child_builder.spawn((
-                                Sprite {
-                                    image: weapon.sprite_handle.clone(),
-                                    ..Default::default()
-                                },
-                                Transform {
-                                    scale: Vec3::splat(bullet_scale),
-                                    translation: Vec3::new(0.0, 0.0, 1.0), // change Z for sprite so that this draws above the background
-                                    ..Default::default()
-                                },
-                                Visibility::Visible,
-                            ));
-                        });
+                        ));
+                    });
                 }
             }
             WeaponSubtype::Laser { width, max_dist } => {
                 commands
                     .spawn((
-                        Laser::new(weapon.damage, event.hostile, width, max_dist),
+                        Laser::new(
+                            damage,
+                            event.hostile,
+                            width,
+                            max_dist,
+                            weapon.piercing,
+                            source,
+                        ),
+                        RunScoped,
                         Lifetime::new(weapon.lifetime),
                         Transform {
-                            // overwrite transform
-                            scale: Vec3::new(1.0, 20.0, 1.0),
-                            translation: transform.translation
-                                + transform.rotation * Vec3::new(0.0, 200.0, 1.0), // change Z for sprite so that this draws above the background
+                            // the beam starts at the muzzle. change Z for sprite so that this draws above the background
+                            translation: transform.translation + Vec3::Z,
                             rotation: transform.rotation,
+                            ..Default::default()
                         },
                         Visibility::Visible,
                     ))
                     .with_children(|child_builder| {
-                        // scale down bullet. this is because many bullets of different sizes will share the same sprite.
+                        // stretch the sprite out along the beam. its length gets updated as the beam hits things.
                         child_builder.spawn((
                             Sprite {
                                 image: weapon.sprite_handle.clone(),
+                                custom_size: Some(Vec2::new(width * 2.0, max_dist)),
+                                anchor: Anchor::BottomCenter,
                                 ..Default::default()
                             },
                             Transform::IDENTITY,
@@ -362,9 +455,32 @@ fn player_gun_system(
     Ok(())
 }
 
+// turrets fire using their own intent, but inherit velocity from the entity they're mounted on
+fn turret_gun_system(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut WeaponData, &Intent, &ChildOf), With<Turret>>,
+    parents: Query<&Physics>,
+    mut event_writer: EventWriter<WeaponFired>,
+) {
+    for (entity, mut weapon, intent, child_of) in query.iter_mut() {
+        if intent.fire && weapon.timer.tick(time.delta()).finished() {
+            let velocity = parents
+                .get(child_of.parent())
+                .map(|physics| physics.velocity)
+                .unwrap_or(Vec3::ZERO);
+            event_writer.write(WeaponFired::new(entity, velocity, true, weapon.weapon_type));
+            weapon.timer.reset();
+        }
+    }
+}
+
 fn enemy_gun_system(
     time: Res<Time>,
-    mut query: Query<(Entity, &mut WeaponData, &Physics, &Intent), With<Enemy>>,
+    // beam weapons charge up first, see beam_charge_system
+    mut query: Query<
+        (Entity, &mut WeaponData, &Physics, &Intent),
+        (With<Enemy>, Without<BeamCharge>),
+    >,
     mut event_writer: EventWriter<WeaponFired>,
 ) {
     for (entity, mut weapon, physics, intent) in query.iter_mut() {
@@ -389,7 +505,10 @@ impl Plugin for GunCollectionPlugin {
             (
                 gun_fire_system,
                 player_gun_system,
-                enemy_gun_system, /* , slug_gun_fire_system */
+                enemy_gun_system,
+                beam_charge_system,
+                turret_gun_system,
+                homing_system, /* , slug_gun_fire_system */
             )
                 .run_if(in_state(GameState::InGame)),
         );
@@ -1,11 +1,21 @@
 use bevy::prelude::*;
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, Default)]
 pub struct Physics {
     pub mass: f32,
     pub velocity: Vec3,
     pub friction: f32,
     pub gravity: Vec3,
+    // how far the entity moved during the last physics step, so collision can sweep along the path it took.
+    // zero until the entity has moved once.
+    pub last_step: Vec3,
+}
+
+impl Physics {
+    // where the entity was before the last physics step
+    pub fn previous_translation(&self, transform: &Transform) -> Vec3 {
+        transform.translation - self.last_step
+    }
 }
 
 pub fn linear_physics(
@@ -34,6 +44,7 @@ pub fn linear_physics(
             physics.velocity += velocity.normalize() * acceleration * time.delta_secs();
         }
 
-        transform.translation += physics.velocity * time.delta_secs();
+        physics.last_step = physics.velocity * time.delta_secs();
+        transform.translation += physics.last_step;
     }
 }
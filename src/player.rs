@@ -6,10 +6,13 @@ use bevy::prelude::*;
 use crate::{
     body_type_stats::PlaneMovementStats,
     config::GameConfig,
-    events::PlayerDeath,
+    events::{DamageSource, PlayerDeath, PlayerHit},
     gamestate::GameState,
     input::Intent,
-    misc::{CollisionRadius, HP, VerticallyBounded},
+    misc::{
+        CollisionRadius, HP, VerticallyBounded,
+        contact_damage::{DealsContactDamage, TakesContactDamage},
+    },
     mods::{
         body::{BodyType, BomberBody, HeavyBody, MeleeBody, NormalBody, NukeBody},
         engines::{EngineType, GungineEngine, NormalEngine, SuperboostEngine},
@@ -17,6 +20,7 @@ use crate::{
         // Recalculated,
     },
     physics::Physics,
+    reset::RunScoped,
     sprite::{AnimationIndices, TextureAtlasHashMap},
     userdata::UserData,
 };
@@ -58,6 +62,7 @@ pub fn add_player(
     let mut root = commands.spawn((
         Visibility::Visible,
         Player,
+        RunScoped,
         Intent::default(),
         HP {
             hp: 100.0,
@@ -69,6 +74,7 @@ pub fn add_player(
             velocity: Vec3::new(0.0, 0.0, 0.0),
             gravity: Vec3::new(0.0, -4.0, 0.0),
             friction: 0.99,
+            ..Default::default()
         },
         VerticallyBounded,
         PlayerStats::default(),
@@ -77,6 +83,12 @@ pub fn add_player(
             turn_speed: 4.0,
         },
         CollisionRadius(10.0),
+        (
+            TakesContactDamage {},
+            DealsContactDamage {
+                damage_per_second: 40.0,
+            },
+        ),
         Sprite {
             image: player_atlas_handle,
             texture_atlas: Some(TextureAtlas {
@@ -181,20 +193,20 @@ pub fn add_player(
         }
     };
     commands = match userdata.selected_build.1 {
-        BodyType::Normal => commands.insert(NormalBody::default()),
+        BodyType::Normal => commands.insert(NormalBody),
         BodyType::Heavy => commands.insert(HeavyBody::default()),
         BodyType::Melee => commands.insert(MeleeBody::default()),
         BodyType::Nuke => commands.insert(NukeBody::default()),
         BodyType::Bomber => commands.insert(BomberBody::default()),
     };
     match userdata.selected_build.2 {
-        EngineType::Normal => commands.insert(NormalEngine::default()),
+        EngineType::Normal => commands.insert(NormalEngine),
         EngineType::Superboost => commands.insert(SuperboostEngine::new(
             game_config.superboost_acceleration_modifier,
             game_config.superboost_turn_speed_modifier,
         )),
         EngineType::Gungine => {
-            commands.insert(GungineEngine::default());
+            commands.insert(GungineEngine);
             commands.with_children(|e| {
                 e.spawn((
                     Transform::IDENTITY,
@@ -228,6 +240,13 @@ pub fn plane_intent_movement_system(
         }
     }
 }
+// the player's sprite shows its rotation through animation frames, everything else is rotated directly.
+pub fn turn_angle_rotation_system(mut query: Query<(&TurnAngle, &mut Transform), Without<Player>>) {
+    for (turn_angle, mut transform) in query.iter_mut() {
+        transform.rotation = Quat::from_rotation_z(**turn_angle);
+    }
+}
+
 pub fn animate_player_sprite(
     mut query: Query<(&mut Sprite, &AnimationIndices, &TurnAngle), With<Player>>,
 ) -> Result<(), BevyError> {
@@ -244,15 +263,41 @@ pub fn animate_player_sprite(
     Ok(())
 }
 
+// whatever last hit the player, which player entity it was, and the hp it left them on.
+// a death is only blamed on the hit if the hit is what took them to zero,
+// so flying out of bounds or ramming an enemy after being shot doesn't count as being shot down.
+#[derive(Default)]
+pub struct LastHit(Option<(Entity, DamageSource, f32)>);
+
+impl LastHit {
+    pub fn record(&mut self, entity: Entity, source: DamageSource, hp: f32) {
+        self.0 = Some((entity, source, hp));
+    }
+
+    // the player is respawned every run, so hits on a previous player entity are ignored
+    pub fn killed_by(&self, entity: Entity) -> Option<DamageSource> {
+        self.0
+            .filter(|(hit_entity, _, hp)| *hit_entity == entity && *hp <= 0.0)
+            .map(|(_, source, _)| source)
+    }
+}
+
 pub fn player_death_detection_system(
     // mut commands: Commands,
     mut event_writer: EventWriter<PlayerDeath>,
+    mut hit_events: EventReader<PlayerHit>,
+    mut last_hit: Local<LastHit>,
     query: Query<(Entity, &mut HP), With<Player>>,
 ) {
-    for (_, hp) in query.iter() {
+    for (entity, hp) in query.iter() {
+        if let Some(event) = hit_events.read().last() {
+            last_hit.record(entity, event.source, hp.hp);
+        }
         if hp.hp <= 0.0 {
-            // kill player if hp drops <= 0
-            event_writer.write(PlayerDeath);
+            // kill player if hp drops <= 0.
+            event_writer.write(PlayerDeath {
+                killed_by: last_hit.killed_by(entity),
+            });
         }
     }
 }
@@ -272,18 +317,46 @@ pub fn player_death_system_stage_one(
     }
     // clear all playerdeath events
     // TODO: multiplayer - PlayerDeath will need to be updated to signal which player died.
+    // it already carries what killed them.
 }
 
-pub fn player_death_system_stage_two(
-    mut commands: Commands,
-    events: EventReader<PlayerDeath>,
-    query: Query<(Entity, &Player)>,
-) {
-    // TODO: don't actually despawn the player, instead have a function `reset` that gets all the player's components and calls reset on them or something
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::DamageType;
+
+    fn source() -> DamageSource {
+        DamageSource {
+            entity: Entity::from_raw(2),
+            weapon_type: WeaponType::MachineGun,
+            damage_type: DamageType::Kinetic,
+        }
+    }
+
+    #[test]
+    fn killing_hit_is_blamed() {
+        let player = Entity::from_raw(1);
+        let mut last_hit = LastHit::default();
+        last_hit.record(player, source(), -5.0);
+        assert_eq!(
+            last_hit.killed_by(player).map(|source| source.entity),
+            Some(source().entity)
+        );
+    }
+
+    #[test]
+    fn drain_after_hit_is_not_blamed_on_the_hit() {
+        let player = Entity::from_raw(1);
+        let mut last_hit = LastHit::default();
+        // shot down to 10 hp, then drained the rest of the way by flying out of bounds
+        last_hit.record(player, source(), 10.0);
+        assert!(last_hit.killed_by(player).is_none());
+    }
 
-    if !events.is_empty() && !query.is_empty() {
-        // despawn player
-        info!("player died");
-        commands.entity(query.single().unwrap().0).despawn();
+    #[test]
+    fn hits_from_a_previous_run_are_ignored() {
+        let mut last_hit = LastHit::default();
+        last_hit.record(Entity::from_raw(1), source(), -5.0);
+        assert!(last_hit.killed_by(Entity::from_raw(3)).is_none());
     }
 }
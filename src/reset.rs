@@ -0,0 +1,109 @@
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+
+use crate::{
+    gamestate::GameState,
+    misc::{HeatTracker, combo::ComboCounter, hitstun::HitStun, score::ScoreTracker},
+};
+
+// marks an entity as owned by the current run. every RunScoped entity is despawned when the run is torn down.
+// only needs to be added to root entities, children are despawned along with their parents.
+#[derive(Component, Default)]
+pub struct RunScoped;
+
+// resources that hold per-run state, and need to be reset between runs.
+pub trait RunReset: Resource {
+    fn reset(&mut self);
+}
+
+// schedule that runs whenever a run ends, whether by restarting, quitting to the main menu, or dying.
+// plugins that own per-run state should add their cleanup systems to this schedule.
+// restarting and quitting from the pause menu go through it as well, so gamestate.rs only declares the states
+// and never tears anything down itself.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RunTeardown;
+
+pub fn reset_run_resource_system<R: RunReset>(mut resource: ResMut<R>) {
+    resource.reset();
+}
+
+pub fn despawn_run_scoped_system(mut commands: Commands, query: Query<Entity, With<RunScoped>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn run_teardown_schedule(world: &mut World) {
+    info!("tearing down run");
+    world.run_schedule(RunTeardown);
+}
+
+fn restart_run_system(mut game_state: ResMut<NextState<GameState>>) {
+    game_state.set(GameState::InGame);
+}
+
+pub trait RunResetAppExt {
+    fn reset_on_run_teardown<R: RunReset>(&mut self) -> &mut Self;
+}
+
+impl RunResetAppExt for App {
+    fn reset_on_run_teardown<R: RunReset>(&mut self) -> &mut Self {
+        self.add_systems(RunTeardown, reset_run_resource_system::<R>)
+    }
+}
+
+impl RunReset for HeatTracker {
+    fn reset(&mut self) {
+        HeatTracker::reset(self);
+    }
+}
+
+impl RunReset for ComboCounter {
+    fn reset(&mut self) {
+        ComboCounter::reset(self);
+    }
+}
+
+impl RunReset for ScoreTracker {
+    fn reset(&mut self) {
+        **self = 0;
+    }
+}
+
+impl RunReset for HitStun {
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+pub struct RunResetPlugin;
+
+impl Plugin for RunResetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_schedule(RunTeardown)
+            .add_systems(RunTeardown, despawn_run_scoped_system)
+            .reset_on_run_teardown::<HeatTracker>()
+            .reset_on_run_teardown::<ComboCounter>()
+            .reset_on_run_teardown::<ScoreTracker>()
+            .reset_on_run_teardown::<HitStun>()
+            .add_systems(
+                OnEnter(GameState::Restarting),
+                (run_teardown_schedule, restart_run_system).chain(),
+            )
+            .add_systems(
+                OnTransition {
+                    exited: GameState::Paused,
+                    entered: GameState::MainMenu,
+                },
+                run_teardown_schedule,
+            )
+            // the last frame of the run stays on screen during GameEnding, so only tear down once it's over.
+            // retrying goes through Restarting, which tears down on its own.
+            .add_systems(
+                OnTransition {
+                    exited: GameState::GameEnding,
+                    entered: GameState::MainMenu,
+                },
+                run_teardown_schedule,
+            );
+    }
+}
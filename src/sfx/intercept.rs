@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+use crate::events::ProjectileIntercepted;
+
+use super::Sfx;
+
+pub fn intercept_sound_effect_system(
+    mut intercept_events: EventReader<ProjectileIntercepted>,
+    query_sfx: Query<&AudioSink, With<Sfx>>,
+) {
+    // shares the hit sound for now, restarting it like a hit would
+    if !intercept_events.is_empty() {
+        let Ok(q) = query_sfx.single() else {
+            return;
+        };
+        q.play();
+        intercept_events.clear();
+    }
+}
@@ -4,8 +4,12 @@ use bevy::prelude::*;
 use crate::gamestate::GameState;
 
 mod enemy_hit;
+mod intercept;
+mod music;
 
 use enemy_hit::enemy_hit_sound_effect_system;
+use intercept::intercept_sound_effect_system;
+use music::heat_tier_music_system;
 
 #[derive(Component)]
 pub struct Sfx;
@@ -14,7 +18,12 @@ impl Plugin for Sfx {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            enemy_hit_sound_effect_system.run_if(in_state(GameState::InGame)),
+            (
+                enemy_hit_sound_effect_system,
+                intercept_sound_effect_system,
+                heat_tier_music_system,
+            )
+                .run_if(in_state(GameState::InGame)),
         );
     }
 }
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+
+use crate::{config::GameConfig, events::HeatTierChanged, reset::RunScoped};
+
+// the currently playing music track. there's at most one.
+#[derive(Component)]
+pub struct MusicTrack;
+
+// switches music when the heat tier changes, if the new tier has a track configured.
+// tiers without a track keep whatever is already playing.
+pub fn heat_tier_music_system(
+    mut commands: Commands,
+    mut events: EventReader<HeatTierChanged>,
+    game_config: Res<GameConfig>,
+    asset_server: Res<AssetServer>,
+    tracks: Query<Entity, With<MusicTrack>>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+    let Some(path) = game_config
+        .heat_tiers
+        .get(event.current)
+        .and_then(|tier| tier.music.clone())
+    else {
+        return;
+    };
+    info!("switching music to {}", path);
+    for entity in tracks.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        MusicTrack,
+        RunScoped,
+        AudioPlayer::new(asset_server.load::<AudioSource>(path)),
+        PlaybackSettings::LOOP,
+    ));
+}
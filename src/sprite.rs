@@ -1,7 +1,5 @@
 use bevy::{platform::collections::HashMap, prelude::*};
 
-use crate::player::Player;
-
 #[derive(Resource, Deref, DerefMut, Default)]
 pub struct TextureAtlasHashMap(HashMap<String, (Handle<Image>, Handle<TextureAtlasLayout>)>);
 
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use crate::{
+    gamestate::{GameEndingTimer, GameState},
+    misc::stats::RunStats,
+};
+
+use super::menu::{MenuAction, MenuActivated, MenuScreen, menu_item, menu_label, spawn_menu_root};
+
+pub fn spawn_game_over_menu(commands: &mut Commands, stats: &RunStats) {
+    let root = spawn_menu_root(commands, "Game Over");
+    commands.entity(root).with_children(|parent| {
+        parent.spawn(menu_label(format!("Score: {}", stats.final_score)));
+        if stats.new_high_score {
+            parent.spawn(menu_label("New high score!"));
+        }
+
+        let mut kills = stats.kills.iter().collect::<Vec<_>>();
+        kills.sort_by_key(|(enemy_type, _)| format!("{enemy_type:?}"));
+        for (enemy_type, count) in kills {
+            parent.spawn(menu_label(format!(
+                "{enemy_type:?} enemies killed: {count}"
+            )));
+        }
+
+        let mut weapons = stats.weapons.iter().collect::<Vec<_>>();
+        weapons.sort_by_key(|(weapon_type, _)| format!("{weapon_type:?}"));
+        for (weapon_type, weapon_stats) in weapons {
+            parent.spawn(menu_label(format!(
+                "{weapon_type:?}: {:.0} damage, {} kills, {} intercepts",
+                weapon_stats.damage, weapon_stats.kills, weapon_stats.intercepts
+            )));
+        }
+
+        parent.spawn(menu_label(format!("Max combo: {}", stats.max_combo)));
+        parent.spawn(menu_label(format!(
+            "Waves survived: {}",
+            stats.waves_survived
+        )));
+        parent.spawn(menu_label(format!(
+            "Damage taken: {:.0} from bullets, {:.0} from the upper bound, {:.0} from the water",
+            stats.damage_taken_bullets,
+            stats.damage_taken_upper_bound,
+            stats.damage_taken_lower_bound
+        )));
+        if let Some(weapon_type) = stats.killed_by {
+            parent.spawn(menu_label(format!("Shot down by: {weapon_type:?}")));
+        }
+        let minutes = (stats.time_alive / 60.0) as u32;
+        let seconds = stats.time_alive % 60.0;
+        parent.spawn(menu_label(format!("Time alive: {minutes}:{seconds:04.1}")));
+
+        parent.spawn(menu_item(0, MenuAction::RestartRun, "Retry"));
+        parent.spawn(menu_item(1, MenuAction::QuitToMenu, "Main Menu"));
+    });
+}
+
+// waits a short moment before showing the game over screen, so that inputs from the end of the run don't skip it.
+pub fn game_ending_system(
+    time: Res<Time>,
+    mut timer: ResMut<GameEndingTimer>,
+    mut screen: ResMut<MenuScreen>,
+) {
+    if timer.tick(time.delta()).just_finished() {
+        *screen = MenuScreen::GameOver;
+    }
+}
+
+pub fn game_over_menu_system(
+    mut events: EventReader<MenuActivated>,
+    screen: Res<MenuScreen>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for event in events.read() {
+        if *screen != MenuScreen::GameOver {
+            continue;
+        }
+        match event.action {
+            MenuAction::RestartRun => game_state.set(GameState::Restarting),
+            MenuAction::QuitToMenu => game_state.set(GameState::MainMenu),
+            _ => {}
+        }
+    }
+}
+
+fn teardown_game_over_ui(mut screen: ResMut<MenuScreen>, mut timer: ResMut<GameEndingTimer>) {
+    *screen = MenuScreen::None;
+    timer.reset();
+}
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (game_ending_system, game_over_menu_system).run_if(in_state(GameState::GameEnding)),
+        )
+        .add_systems(OnExit(GameState::GameEnding), teardown_game_over_ui);
+    }
+}
@@ -1,149 +1,284 @@
+use bevy::color::palettes::css::{GOLD, LIGHT_SKY_BLUE, ORANGE_RED, RED, YELLOW};
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
 
+use crate::config::GameConfig;
+use crate::events::HeatTierChanged;
 use crate::gamestate::GameState;
+use crate::misc::HP;
+use crate::misc::HeatTracker;
 use crate::misc::combo::ComboCounter;
+use crate::misc::hitstun::in_game_or_hitstun;
 use crate::misc::score::ScoreTracker;
+use crate::player::Player;
+use crate::userdata::UserData;
+
+const HUD_FONT_SIZE: f32 = 24.0;
+const HP_BAR_WIDTH: f32 = 200.0;
 
 #[derive(Component)]
-pub struct ScoreText;
+pub struct HudRoot;
 
 #[derive(Component)]
+pub struct ScoreText;
 
+#[derive(Component)]
 pub struct ComboText;
 
-pub fn hud_setup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    score: Query<&ScoreText>,
-    combo: Query<&ComboText>,
-) {
-    if score.is_empty() && combo.is_empty() {
-        let bold_font: Handle<Font> = asset_server.load("fonts/FiraSans-Bold.ttf");
-        let normal_font: Handle<Font> = asset_server.load("fonts/FiraMono-Medium.ttf");
-
-        let bold_style = TextStyle {
-            font: bold_font,
-            font_size: 30.0,
-            color: Color::White,
-        };
+#[derive(Component)]
+pub struct ComboDecayBar;
 
-        let normal_style = TextStyle {
-            font: normal_font,
-            font_size: 30.0,
-            color: Color::GOLD,
-        };
+#[derive(Component)]
+pub struct HpBar;
 
-        // Text with multiple sections
-        commands.spawn(
-            (
-                // Create a TextBundle that has a Text with a list of sections.
-                Text2dBundle {
-                    text: Text {
-                        sections: vec![
-                            TextSection::new("Score:", bold_style),
-                            TextSection::new("", normal_style),
-                        ],
-                        alignment: todo!(),
-                        linebreak_behaviour: todo!(),
-                    },
+#[derive(Component)]
+pub struct HeatText;
+
+#[derive(Component)]
+pub struct FpsText;
 
-                    text_anchor: todo!(),
-                    text_2d_bounds: todo!(),
-                    transform: todo!(),
-                    global_transform: todo!(),
-                    visibility: todo!(),
-                    computed_visibility: todo!(),
-                }
-            ),
-        );
+pub fn hud_setup(mut commands: Commands, hud: Query<Entity, With<HudRoot>>) {
+    if !hud.is_empty() {
+        // hud is only spawned once, and is hidden or shown depending on the gamestate afterwards.
+        return;
     }
-    //     [
-    //         TextSection::new(
-    //             "Score: ",
-    //             TextStyle {
-    //                 font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-    //                 font_size: 30.0,
-    //                 color: Color::WHITE,
-    //             },
-    //         ),
-    //         TextSection::from_style(TextStyle {
-    //             font: asset_server.load("fonts/FiraMono-Medium.ttf"),
-    //             font_size: 30.0,
-    //             color: Color::GOLD,
-    //         }),
-    //     ])
-    //     .with_style(Style {
-    //         position_type: PositionType::Absolute,
-    //         position: UiRect {
-    //             top: Val::Px(5.0),
-    //             left: Val::Px(15.0),
-    //             ..default()
-    //         },
-    //         ..default()
-    //     }),
-    //     ScoreText,
-    // ));
-    //     commands.spawn((
-    //     // Create a TextBundle that has a Text with a list of sections.
-    //         TextBundle::from_sections([TextSection::from_style(TextStyle {
-    //             font: asset_server.load("fonts/FiraMono-Medium.ttf"),
-    //             font_size: 30.0,
-    //             color: Color::GOLD,
-    //         })])
-    //         .with_style(Style {
-    //             position_type: PositionType::Absolute,
-    //             position: UiRect {
-    //                 top: Val::Px(25.0),
-    //                 left: Val::Px(15.0),
-    //                 ..default()
-    //             },
-    //             ..default()
-    //         }),
-    //         ComboText,
-    //     ));
-    // }
+
+    let label_font = TextFont {
+        font_size: HUD_FONT_SIZE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            HudRoot,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                left: Val::Px(15.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            Visibility::Visible,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((Text::new("Score: "), label_font.clone()))
+                .with_child((
+                    TextSpan::default(),
+                    label_font.clone(),
+                    TextColor(GOLD.into()),
+                    ScoreText,
+                ));
+
+            parent
+                .spawn((Text::new("Combo: "), label_font.clone()))
+                .with_child((
+                    TextSpan::default(),
+                    label_font.clone(),
+                    TextColor(GOLD.into()),
+                    ComboText,
+                ));
+
+            // combo decay bar, shrinks as the combo timer runs out
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(HP_BAR_WIDTH),
+                        height: Val::Px(4.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(GOLD.into()),
+                    ComboDecayBar,
+                ));
+
+            // hp bar
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(HP_BAR_WIDTH),
+                        height: Val::Px(12.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(RED.into()),
+                    HpBar,
+                ));
+
+            parent
+                .spawn((Text::new("Heat: "), label_font.clone()))
+                .with_child((
+                    TextSpan::default(),
+                    label_font.clone(),
+                    TextColor(ORANGE_RED.into()),
+                    HeatText,
+                ));
+
+            parent
+                .spawn((Text::new("FPS: "), label_font.clone()))
+                .with_child((TextSpan::default(), label_font, FpsText));
+        });
 }
 
-pub fn score_text_update(
-    mut query: Query<(&mut Text, &mut Visibility), With<ScoreText>>,
-    score: Res<ScoreTracker>,
+pub fn hud_visibility_system(
+    mut query: Query<&mut Visibility, With<HudRoot>>,
     gamestate: Res<State<GameState>>,
 ) {
-    let (mut text, mut visibility) = query.single_mut();
-    if gamestate.is_changed() {
-        *visibility = if gamestate.0 == GameState::InGame {
-            Visibility::Inherited
-        } else {
-            Visibility::Hidden
-        };
+    if !gamestate.is_changed() {
+        return;
+    }
+    let target = if in_game_or_hitstun(gamestate) {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut visibility in query.iter_mut() {
+        visibility.set_if_neq(target);
     }
-    text.sections[1].value = score.to_string();
 }
+
+pub fn score_text_update(
+    mut query: Query<&mut TextSpan, With<ScoreText>>,
+    score: Res<ScoreTracker>,
+) -> Result<(), BevyError> {
+    let mut text = query.single_mut()?;
+    **text = score.to_string();
+    Ok(())
+}
+
 pub fn combo_text_update(
-    mut query: Query<(&mut Text, &mut Visibility), With<ComboText>>,
+    mut text_query: Query<&mut TextSpan, With<ComboText>>,
+    mut bar_query: Query<&mut Node, With<ComboDecayBar>>,
     combo: Res<ComboCounter>,
-    gamestate: Res<State<GameState>>,
-) {
-    let (mut text, mut visibility) = query.single_mut();
-    if gamestate.is_changed() {
-        *visibility = if gamestate.0 == GameState::InGame {
-            Visibility::Inherited
+) -> Result<(), BevyError> {
+    let mut text = text_query.single_mut()?;
+    **text = format!("{}x", combo.multiplier());
+
+    let mut bar = bar_query.single_mut()?;
+    bar.width = if combo.count > 0 {
+        Val::Percent(combo.decay_fraction_remaining() * 100.0)
+    } else {
+        Val::Percent(0.0)
+    };
+    Ok(())
+}
+
+pub fn hp_bar_update(
+    mut bar_query: Query<&mut Node, With<HpBar>>,
+    player: Query<&HP, With<Player>>,
+) -> Result<(), BevyError> {
+    let mut bar = bar_query.single_mut()?;
+    let Ok(hp) = player.single() else {
+        // player may have been despawned already
+        return Ok(());
+    };
+    bar.width = Val::Percent((hp.hp / hp.max).clamp(0.0, 1.0) * 100.0);
+    Ok(())
+}
+
+pub fn heat_text_update(
+    mut query: Query<&mut TextSpan, With<HeatText>>,
+    heat_tracker: Res<HeatTracker>,
+    game_config: Res<GameConfig>,
+) -> Result<(), BevyError> {
+    let mut text = query.single_mut()?;
+    let tier_name = game_config
+        .heat_tiers
+        .get(heat_tracker.tier())
+        .map(|tier| tier.name.as_str())
+        .unwrap_or_default();
+    **text = format!(
+        "{} {:.2} (wave {})",
+        tier_name,
+        heat_tracker.heat(),
+        heat_tracker.spawned_waves()
+    );
+    Ok(())
+}
+
+// seconds for the heat text to fade back to its normal color after a tier change
+const HEAT_FLASH_SECS: f32 = 1.5;
+
+// flashes the heat text when the tier changes, yellow going up and blue going down
+pub fn heat_text_flash(
+    mut query: Query<&mut TextColor, With<HeatText>>,
+    mut tier_events: EventReader<HeatTierChanged>,
+    time: Res<Time>,
+) -> Result<(), BevyError> {
+    let mut color = query.single_mut()?;
+    if let Some(event) = tier_events.read().last() {
+        color.0 = if event.current > event.previous {
+            YELLOW.into()
         } else {
-            Visibility::Hidden
+            LIGHT_SKY_BLUE.into()
         };
     }
+    let t = (time.delta_secs() / HEAT_FLASH_SECS).min(1.0);
+    color.0 = color.0.mix(&ORANGE_RED.into(), t);
+    Ok(())
+}
+
+pub fn fps_text_update(
+    mut query: Query<(&mut TextSpan, &ChildOf), With<FpsText>>,
+    mut parent_visibility: Query<&mut Visibility>,
+    diagnostics: Res<DiagnosticsStore>,
+    userdata: Res<UserData>,
+) -> Result<(), BevyError> {
+    let (mut text, child_of) = query.single_mut()?;
+    let mut visibility = parent_visibility.get_mut(child_of.parent())?;
+    // only touch visibility when it actually changes, so change detection doesn't fire every frame
+    if !userdata.display_fps {
+        visibility.set_if_neq(Visibility::Hidden);
+        return Ok(());
+    }
+    visibility.set_if_neq(Visibility::Inherited);
 
-    text.sections[0].value = format!("{}x", combo.multiplier());
+    if let Some(fps) = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+    {
+        **text = format!("{fps:.0}");
+    }
+    Ok(())
 }
 
 pub struct HUDPlugin;
 
 impl Plugin for HUDPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(hud_setup.in_schedule(OnEnter(GameState::InGame)))
-            .add_system(
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        app.add_systems(OnEnter(GameState::InGame), hud_setup)
+            .add_systems(Update, hud_visibility_system)
+            .add_systems(
                 Update,
-                (combo_text_update, score_text_update).run_if(in_state(GameState::InGame)),
+                (
+                    score_text_update,
+                    combo_text_update,
+                    hp_bar_update,
+                    heat_text_update,
+                    heat_text_flash,
+                    fps_text_update,
+                )
+                    .run_if(in_game_or_hitstun),
             );
     }
 }
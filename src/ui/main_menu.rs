@@ -1,29 +1,131 @@
 use crate::{
     gamestate::GameState,
-    input::InputMode,
-    mods::{engines::EngineType, guns::WeaponType},
+    mods::{body::BodyType, engines::EngineType, guns::WeaponType},
     userdata::UserData,
 };
 use bevy::prelude::*;
 
-#[derive(Resource, DerefMut, Deref)]
-pub struct MainMenuDebounceTimer(pub Timer);
+use super::menu::{
+    MenuAction, MenuActivated, MenuScreen, cycle, menu_item, menu_label, spawn_menu_root,
+};
 
-pub fn setup_main_menu_ui() {}
+// missile and gungine weapons, and the submarine engine, are not selectable yet.
+const SELECTABLE_WEAPONS: [WeaponType; 4] = [
+    WeaponType::MachineGun,
+    WeaponType::SpreadGun,
+    WeaponType::SlugGun,
+    WeaponType::Laser,
+];
+const SELECTABLE_BODIES: [BodyType; 5] = [
+    BodyType::Normal,
+    BodyType::Heavy,
+    BodyType::Melee,
+    BodyType::Nuke,
+    BodyType::Bomber,
+];
+const SELECTABLE_ENGINES: [EngineType; 3] = [
+    EngineType::Normal,
+    EngineType::Superboost,
+    EngineType::Gungine,
+];
 
-pub fn main_menu_ui_system(
-    time: Res<Time>,
-    mut timer: ResMut<MainMenuDebounceTimer>,
-    mut state: ResMut<NextState<GameState>>,
-    // mut data: ResMut<UserData>,
-) {
-    // do ui stuff, but placeholder for now
+pub fn setup_main_menu_ui(mut screen: ResMut<MenuScreen>) {
+    *screen = MenuScreen::Main;
+}
 
-    // with no ui stuff and choices to make, should enter the in-game state after 1 frame
+pub fn teardown_main_menu_ui(mut screen: ResMut<MenuScreen>) {
+    *screen = MenuScreen::None;
+}
+
+pub fn spawn_main_menu(commands: &mut Commands) {
+    let root = spawn_menu_root(commands, "LUFT");
+    commands.entity(root).with_children(|parent| {
+        parent.spawn(menu_item(0, MenuAction::Start, "Start"));
+        parent.spawn(menu_item(1, MenuAction::Hangar, "Hangar"));
+        parent.spawn(menu_item(2, MenuAction::Settings, "Settings"));
+        parent.spawn(menu_item(3, MenuAction::HighScores, "High Scores"));
+        parent.spawn(menu_item(4, MenuAction::Quit, "Quit"));
+    });
+}
 
-    timer.tick(time.delta());
-    if timer.finished() {
-        state.set(GameState::InGame);
-        timer.reset();
+pub fn spawn_hangar_menu(commands: &mut Commands, userdata: &UserData) {
+    let (weapon, body, engine) = &userdata.selected_build;
+    let root = spawn_menu_root(commands, "Hangar");
+    commands.entity(root).with_children(|parent| {
+        parent.spawn(menu_item(
+            0,
+            MenuAction::CycleWeapon,
+            format!("Weapon: < {weapon:?} >"),
+        ));
+        parent.spawn(menu_item(
+            1,
+            MenuAction::CycleBody,
+            format!("Body: < {body:?} >"),
+        ));
+        parent.spawn(menu_item(
+            2,
+            MenuAction::CycleEngine,
+            format!("Engine: < {engine:?} >"),
+        ));
+        parent.spawn(menu_item(3, MenuAction::Back, "Back"));
+    });
+}
+
+pub fn spawn_high_scores_menu(commands: &mut Commands, userdata: &UserData) {
+    let (score, build) = &userdata.high_score;
+    let root = spawn_menu_root(commands, "High Scores");
+    commands.entity(root).with_children(|parent| {
+        if *score == 0 {
+            parent.spawn(menu_label("No high score yet"));
+        } else {
+            parent.spawn(menu_label(format!("{score}  -  {build}")));
+        }
+        parent.spawn(menu_item(0, MenuAction::Back, "Back"));
+    });
+}
+
+pub fn main_menu_action_system(
+    mut events: EventReader<MenuActivated>,
+    mut screen: ResMut<MenuScreen>,
+    mut state: ResMut<NextState<GameState>>,
+    mut userdata: ResMut<UserData>,
+) {
+    for event in events.read() {
+        match (*screen, event.action) {
+            (MenuScreen::Main, MenuAction::Start) => state.set(GameState::InGame),
+            (MenuScreen::Main, MenuAction::Hangar) => *screen = MenuScreen::Hangar,
+            (MenuScreen::Main, MenuAction::Settings) => *screen = MenuScreen::Settings,
+            (MenuScreen::Main, MenuAction::HighScores) => *screen = MenuScreen::HighScores,
+            (MenuScreen::Main, MenuAction::Quit) => state.set(GameState::Quitting),
+            (MenuScreen::Hangar, MenuAction::CycleWeapon) => {
+                userdata.selected_build.0 = cycle(
+                    &SELECTABLE_WEAPONS,
+                    &userdata.selected_build.0,
+                    event.direction,
+                );
+            }
+            (MenuScreen::Hangar, MenuAction::CycleBody) => {
+                userdata.selected_build.1 = cycle(
+                    &SELECTABLE_BODIES,
+                    &userdata.selected_build.1,
+                    event.direction,
+                );
+            }
+            (MenuScreen::Hangar, MenuAction::CycleEngine) => {
+                userdata.selected_build.2 = cycle(
+                    &SELECTABLE_ENGINES,
+                    &userdata.selected_build.2,
+                    event.direction,
+                );
+            }
+            (MenuScreen::Hangar, MenuAction::Back) => {
+                if let Err(e) = userdata.save() {
+                    error!("failed to save userdata: {e}");
+                }
+                *screen = MenuScreen::Main;
+            }
+            (MenuScreen::HighScores, MenuAction::Back) => *screen = MenuScreen::Main,
+            _ => {}
+        }
     }
 }
@@ -0,0 +1,257 @@
+use bevy::color::palettes::css::GOLD;
+use bevy::prelude::*;
+
+use crate::{misc::stats::RunStats, userdata::UserData};
+
+use super::game_over::spawn_game_over_menu;
+use super::main_menu::{spawn_hangar_menu, spawn_high_scores_menu, spawn_main_menu};
+use super::pause::spawn_pause_menu;
+use super::settings::spawn_settings_menu;
+
+pub const MENU_TITLE_FONT_SIZE: f32 = 48.0;
+pub const MENU_FONT_SIZE: f32 = 28.0;
+
+// which menu screen is currently shown. screens are rebuilt from scratch whenever this changes.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuScreen {
+    #[default]
+    None,
+    Main,
+    Hangar,
+    Settings,
+    HighScores,
+    Pause,
+    GameOver,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    // main menu
+    Start,
+    Hangar,
+    Settings,
+    HighScores,
+    Quit,
+    Back,
+    // hangar
+    CycleWeapon,
+    CycleBody,
+    CycleEngine,
+    // pause menu
+    Resume,
+    RestartRun,
+    QuitToMenu,
+    // settings
+    InputMethod,
+    DisplayFps,
+    Deadzone,
+    DesiredFps,
+    Volume,
+    Difficulty,
+    ScreenShake,
+}
+
+impl MenuAction {
+    // the action that the back button (escape / gamepad east) maps to on any screen
+    pub fn is_back(self) -> bool {
+        matches!(self, MenuAction::Back | MenuAction::Resume)
+    }
+}
+
+#[derive(Component)]
+pub struct MenuRoot;
+
+#[derive(Component)]
+pub struct MenuItem {
+    pub index: usize,
+    pub action: MenuAction,
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct MenuCursor(pub usize);
+
+// fired when a menu item is activated.
+// `direction` is 0 when confirmed, and -1 or 1 when the item's value is cycled left or right.
+#[derive(Event, Clone, Copy)]
+pub struct MenuActivated {
+    pub action: MenuAction,
+    pub direction: i32,
+}
+
+pub fn menu_open(screen: Res<MenuScreen>) -> bool {
+    *screen != MenuScreen::None
+}
+
+// spawns a full screen root node with a title. items should be added as children of the returned entity.
+pub fn spawn_menu_root(commands: &mut Commands, title: &str) -> Entity {
+    commands
+        .spawn((
+            MenuRoot,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            // draw menus above the hud
+            GlobalZIndex(1),
+        ))
+        .with_child((
+            Text::new(title),
+            TextFont {
+                font_size: MENU_TITLE_FONT_SIZE,
+                ..default()
+            },
+            Node {
+                margin: UiRect::bottom(Val::Px(24.0)),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+pub fn menu_item(index: usize, action: MenuAction, label: impl Into<String>) -> impl Bundle {
+    (
+        MenuItem { index, action },
+        Button,
+        Text::new(label),
+        TextFont {
+            font_size: MENU_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+    )
+}
+
+// non-interactive line of text, for displaying information
+pub fn menu_label(label: impl Into<String>) -> impl Bundle {
+    (
+        Text::new(label),
+        TextFont {
+            font_size: MENU_FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+    )
+}
+
+// cycles through `options`, starting from `current` and moving by `direction`. confirming (direction 0) moves forward.
+pub fn cycle<T: PartialEq + Clone>(options: &[T], current: &T, direction: i32) -> T {
+    let len = options.len() as i32;
+    let idx = options.iter().position(|e| e == current).unwrap_or(0) as i32;
+    let step = if direction == 0 { 1 } else { direction };
+    options[(idx + step).rem_euclid(len) as usize].clone()
+}
+
+pub fn menu_rebuild_system(
+    mut commands: Commands,
+    screen: Res<MenuScreen>,
+    userdata: Res<UserData>,
+    stats: Res<RunStats>,
+    mut cursor: ResMut<MenuCursor>,
+    mut last_screen: Local<MenuScreen>,
+    roots: Query<Entity, With<MenuRoot>>,
+) {
+    // labels for settings depend on userdata, so rebuild when either changes
+    if !screen.is_changed() && !userdata.is_changed() {
+        return;
+    }
+    if *last_screen != *screen {
+        **cursor = 0;
+        *last_screen = *screen;
+    }
+
+    for entity in roots.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    match *screen {
+        MenuScreen::None => {}
+        MenuScreen::Main => spawn_main_menu(&mut commands),
+        MenuScreen::Hangar => spawn_hangar_menu(&mut commands, &userdata),
+        MenuScreen::Settings => spawn_settings_menu(&mut commands, &userdata),
+        MenuScreen::HighScores => spawn_high_scores_menu(&mut commands, &userdata),
+        MenuScreen::Pause => spawn_pause_menu(&mut commands),
+        MenuScreen::GameOver => spawn_game_over_menu(&mut commands, &stats),
+    }
+}
+
+pub fn menu_navigation_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut cursor: ResMut<MenuCursor>,
+    items: Query<&MenuItem>,
+    interactions: Query<(&Interaction, &MenuItem), Changed<Interaction>>,
+    mut events: EventWriter<MenuActivated>,
+) {
+    let item_count = items.iter().count();
+    if item_count == 0 {
+        return;
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButton| {
+        keyboard_input.just_pressed(key) || gamepads.iter().any(|g| g.just_pressed(button))
+    };
+
+    if pressed(KeyCode::ArrowUp, GamepadButton::DPadUp) {
+        **cursor = (**cursor + item_count - 1) % item_count;
+    }
+    if pressed(KeyCode::ArrowDown, GamepadButton::DPadDown) {
+        **cursor = (**cursor + 1) % item_count;
+    }
+    **cursor = (**cursor).min(item_count - 1);
+
+    let mut activate = |index: usize, direction: i32| {
+        if let Some(item) = items.iter().find(|item| item.index == index) {
+            events.write(MenuActivated {
+                action: item.action,
+                direction,
+            });
+        }
+    };
+
+    // mouse support
+    for (interaction, item) in interactions.iter() {
+        match interaction {
+            Interaction::Hovered => **cursor = item.index,
+            Interaction::Pressed => {
+                **cursor = item.index;
+                activate(item.index, 0);
+            }
+            Interaction::None => {}
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Enter)
+        || gamepads
+            .iter()
+            .any(|g| g.just_pressed(GamepadButton::South))
+    {
+        activate(**cursor, 0);
+    } else if pressed(KeyCode::ArrowLeft, GamepadButton::DPadLeft) {
+        activate(**cursor, -1);
+    } else if pressed(KeyCode::ArrowRight, GamepadButton::DPadRight) {
+        activate(**cursor, 1);
+    } else if pressed(KeyCode::Escape, GamepadButton::East)
+        && let Some(item) = items.iter().find(|item| item.action.is_back())
+    {
+        activate(item.index, 0);
+    }
+}
+
+pub fn menu_highlight_system(
+    cursor: Res<MenuCursor>,
+    mut items: Query<(&MenuItem, &mut TextColor)>,
+) {
+    for (item, mut color) in items.iter_mut() {
+        *color = if item.index == **cursor {
+            TextColor(GOLD.into())
+        } else {
+            TextColor(Color::WHITE)
+        };
+    }
+}
@@ -1,13 +1,17 @@
-use std::time::Duration;
-
 use bevy::prelude::*;
-// pub mod hud;
+pub mod game_over;
+pub mod hud;
 pub mod main_menu;
+pub mod menu;
 pub mod pause;
+pub mod settings;
 
-// pub use hud::*;
+pub use game_over::*;
+pub use hud::*;
 pub use main_menu::*;
+pub use menu::*;
 pub use pause::*;
+pub use settings::*;
 
 use crate::gamestate::GameState;
 
@@ -15,15 +19,26 @@ pub struct GameUIPlugin;
 
 impl Plugin for GameUIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(PausePlugin)
-            .insert_resource(MainMenuDebounceTimer(Timer::new(
-                Duration::from_millis(500),
-                TimerMode::Once,
-            )))
+        app.add_plugins((PausePlugin, HUDPlugin, GameOverPlugin))
+            .add_event::<MenuActivated>()
+            .init_resource::<MenuScreen>()
+            .init_resource::<MenuCursor>()
             .add_systems(OnEnter(GameState::MainMenu), setup_main_menu_ui)
+            .add_systems(OnExit(GameState::MainMenu), teardown_main_menu_ui)
             .add_systems(
                 Update,
-                main_menu_ui_system.run_if(in_state(GameState::MainMenu)),
+                (
+                    menu_rebuild_system,
+                    (
+                        menu_navigation_system,
+                        main_menu_action_system,
+                        settings_action_system,
+                    )
+                        .chain()
+                        .run_if(menu_open),
+                    menu_highlight_system,
+                    apply_volume_system,
+                ),
             );
     }
 }
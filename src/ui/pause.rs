@@ -2,42 +2,69 @@ use std::time::Duration;
 
 use bevy::prelude::*;
 
-use crate::gamestate::GameState;
+use crate::{gamestate::GameState, misc::hitstun::HitStun};
+
+use super::menu::{MenuAction, MenuActivated, MenuScreen, menu_item, spawn_menu_root};
 
 #[derive(Resource, DerefMut, Deref)]
 struct PauseDebounceTimer(Timer);
 
+pub fn spawn_pause_menu(commands: &mut Commands) {
+    let root = spawn_menu_root(commands, "Paused");
+    commands.entity(root).with_children(|parent| {
+        parent.spawn(menu_item(0, MenuAction::Resume, "Resume"));
+        parent.spawn(menu_item(1, MenuAction::RestartRun, "Restart Run"));
+        parent.spawn(menu_item(2, MenuAction::Settings, "Settings"));
+        parent.spawn(menu_item(3, MenuAction::QuitToMenu, "Quit to Main Menu"));
+    });
+}
+
+fn setup_pause_menu_ui(mut screen: ResMut<MenuScreen>) {
+    *screen = MenuScreen::Pause;
+}
+
+fn teardown_pause_menu_ui(mut screen: ResMut<MenuScreen>) {
+    *screen = MenuScreen::None;
+}
+
 fn pause_menu_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    // button_input: Res<ButtonInput<GamepadButton>>,
-    time: Res<Time>,
-    mut pause_debounce_timer: ResMut<PauseDebounceTimer>,
+    mut events: EventReader<MenuActivated>,
+    mut screen: ResMut<MenuScreen>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut pause_debounce_timer: ResMut<PauseDebounceTimer>,
 ) {
-    let esc_pressed = keyboard_input.just_pressed(KeyCode::Escape);
-    // let start_pressed = button_input.just_pressed(GamepadButton {
-    //     gamepad: Gamepad::new(0),
-    //     button_type: GamepadButtonType::Start,
-    // });
-    if pause_debounce_timer.tick(time.delta()).finished() && esc_pressed {
-        game_state.set(GameState::InGame);
-        pause_debounce_timer.reset();
+    for event in events.read() {
+        if *screen != MenuScreen::Pause {
+            continue;
+        }
+        match event.action {
+            MenuAction::Resume => {
+                game_state.set(GameState::InGame);
+                pause_debounce_timer.reset();
+            }
+            MenuAction::RestartRun => game_state.set(GameState::Restarting),
+            MenuAction::Settings => *screen = MenuScreen::Settings,
+            MenuAction::QuitToMenu => game_state.set(GameState::MainMenu),
+            _ => {}
+        }
     }
 }
 
 fn pause_input_handler(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    // button_input: Res<ButtonInput<GamepadButton>>,
+    gamepads: Query<&Gamepad>,
     time: Res<Time>,
     mut pause_debounce_timer: ResMut<PauseDebounceTimer>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut hitstun: ResMut<HitStun>,
 ) {
-    let esc_pressed = keyboard_input.just_pressed(KeyCode::Escape);
-    // let start_pressed = button_input.just_pressed(GamepadButton {
-    //     gamepad: Gamepad::new(0),
-    //     // button_type: GamepadButtonType::Start,
-    // });
-    if pause_debounce_timer.tick(time.delta()).finished() && esc_pressed {
+    let pause_pressed = keyboard_input.just_pressed(KeyCode::Escape)
+        || gamepads
+            .iter()
+            .any(|g| g.just_pressed(GamepadButton::Start));
+    if pause_debounce_timer.tick(time.delta()).finished() && pause_pressed {
+        // hitstun is short enough that it's simply dropped when pausing, the game resumes without it.
+        hitstun.clear();
         game_state.set(GameState::Paused);
         pause_debounce_timer.reset();
     }
@@ -50,10 +77,13 @@ impl Plugin for PausePlugin {
         app.add_systems(
             Update,
             (
-                pause_input_handler.run_if(in_state(GameState::InGame)),
+                pause_input_handler
+                    .run_if(in_state(GameState::InGame).or(in_state(GameState::HitStun))),
                 pause_menu_system.run_if(in_state(GameState::Paused)),
             ),
         )
+        .add_systems(OnEnter(GameState::Paused), setup_pause_menu_ui)
+        .add_systems(OnExit(GameState::Paused), teardown_pause_menu_ui)
         .insert_resource(PauseDebounceTimer(Timer::new(
             Duration::from_millis(200),
             TimerMode::Once,
@@ -0,0 +1,144 @@
+use bevy::audio::{GlobalVolume, Volume};
+use bevy::prelude::*;
+
+use crate::{
+    gamestate::GameState,
+    input::InputMode,
+    userdata::{Difficulty, UserData},
+};
+
+use super::menu::{MenuAction, MenuActivated, MenuScreen, cycle, menu_item, spawn_menu_root};
+
+const INPUT_METHODS: [InputMode; 2] = [InputMode::Keyboard, InputMode::Controller];
+const DESIRED_FPS_OPTIONS: [u32; 5] = [30, 60, 120, 144, 240];
+const DEADZONE_STEP: f32 = 0.05;
+const DEADZONE_MAX: f32 = 0.9;
+const VOLUME_STEP: f32 = 0.1;
+const SCREEN_SHAKE_STEP: f32 = 0.25;
+const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+pub fn spawn_settings_menu(commands: &mut Commands, userdata: &UserData) {
+    let root = spawn_menu_root(commands, "Settings");
+    commands.entity(root).with_children(|parent| {
+        parent.spawn(menu_item(
+            0,
+            MenuAction::InputMethod,
+            format!("Input: < {:?} >", userdata.selected_input_method),
+        ));
+        parent.spawn(menu_item(
+            1,
+            MenuAction::DisplayFps,
+            format!(
+                "Display FPS: < {} >",
+                if userdata.display_fps { "On" } else { "Off" }
+            ),
+        ));
+        parent.spawn(menu_item(
+            2,
+            MenuAction::Deadzone,
+            format!("Deadzone: < {:.2} >", userdata.deadzone_radius),
+        ));
+        parent.spawn(menu_item(
+            3,
+            MenuAction::DesiredFps,
+            format!("Target FPS: < {} >", userdata.desired_fps),
+        ));
+        parent.spawn(menu_item(
+            4,
+            MenuAction::Volume,
+            format!("Volume: < {:.0}% >", userdata.volume * 100.0),
+        ));
+        parent.spawn(menu_item(
+            5,
+            MenuAction::Difficulty,
+            format!("Difficulty: < {:?} >", userdata.difficulty),
+        ));
+        parent.spawn(menu_item(
+            6,
+            MenuAction::ScreenShake,
+            format!("Screen shake: < {:.0}% >", userdata.screen_shake * 100.0),
+        ));
+        parent.spawn(menu_item(7, MenuAction::Back, "Save and Back"));
+    });
+}
+
+// steps `value` by `step` in `direction`, clamped to [0, max]. confirming wraps around back to 0 once max is reached.
+fn step_value(value: f32, step: f32, max: f32, direction: i32) -> f32 {
+    let stepped = if direction == 0 {
+        if value + step > max + step * 0.5 {
+            0.0
+        } else {
+            value + step
+        }
+    } else {
+        value + step * direction as f32
+    };
+    // round to the nearest step to avoid accumulating float error
+    ((stepped / step).round() * step).clamp(0.0, max)
+}
+
+pub fn settings_action_system(
+    mut events: EventReader<MenuActivated>,
+    mut screen: ResMut<MenuScreen>,
+    mut userdata: ResMut<UserData>,
+    state: Res<State<GameState>>,
+) {
+    for event in events.read() {
+        if *screen != MenuScreen::Settings {
+            continue;
+        }
+        match event.action {
+            MenuAction::InputMethod => {
+                userdata.selected_input_method = cycle(
+                    &INPUT_METHODS,
+                    &userdata.selected_input_method,
+                    event.direction,
+                );
+            }
+            MenuAction::DisplayFps => userdata.display_fps = !userdata.display_fps,
+            MenuAction::Deadzone => {
+                userdata.deadzone_radius = step_value(
+                    userdata.deadzone_radius,
+                    DEADZONE_STEP,
+                    DEADZONE_MAX,
+                    event.direction,
+                );
+            }
+            MenuAction::DesiredFps => {
+                userdata.desired_fps =
+                    cycle(&DESIRED_FPS_OPTIONS, &userdata.desired_fps, event.direction);
+            }
+            MenuAction::Volume => {
+                userdata.volume = step_value(userdata.volume, VOLUME_STEP, 1.0, event.direction);
+            }
+            MenuAction::Difficulty => {
+                userdata.difficulty = cycle(&DIFFICULTIES, &userdata.difficulty, event.direction);
+            }
+            MenuAction::ScreenShake => {
+                userdata.screen_shake = step_value(
+                    userdata.screen_shake,
+                    SCREEN_SHAKE_STEP,
+                    1.0,
+                    event.direction,
+                );
+            }
+            MenuAction::Back => {
+                if let Err(e) = userdata.save() {
+                    error!("failed to save userdata: {e}");
+                }
+                // settings can be opened from the main menu or the pause menu
+                *screen = match state.get() {
+                    GameState::Paused => MenuScreen::Pause,
+                    _ => MenuScreen::Main,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn apply_volume_system(userdata: Res<UserData>, mut global_volume: ResMut<GlobalVolume>) {
+    if userdata.is_changed() {
+        global_volume.volume = Volume::Linear(userdata.volume);
+    }
+}
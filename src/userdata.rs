@@ -1,9 +1,13 @@
+use std::path::Path;
+
 use bevy::platform::collections::hash_map::HashMap;
 use bevy::prelude::*;
+use bevy::scene::ron::ser::{PrettyConfig, Serializer};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     input::InputMode,
+    loading::USER_CONFIG_FILE,
     mods::{body::BodyType, engines::EngineType, guns::WeaponType},
 };
 
@@ -21,6 +25,52 @@ pub struct UserData {
     pub display_fps: bool,
     pub deadzone_radius: f32,
     pub desired_fps: u32,
+    // linear, 0.0 to 1.0
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    // scales screen shake, 0.0 turns it off
+    #[serde(default = "default_screen_shake")]
+    pub screen_shake: f32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // the most enemy aim can be off by, in radians
+    pub fn aim_error(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.2,
+            Difficulty::Normal => 0.08,
+            Difficulty::Hard => 0.02,
+        }
+    }
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+fn default_screen_shake() -> f32 {
+    1.0
+}
+
+impl UserData {
+    // writes userdata back to disk, overwriting the file that was loaded on startup
+    pub fn save(&self) -> Result<(), BevyError> {
+        let file = std::fs::File::create(Path::new("assets").join(USER_CONFIG_FILE))?;
+        let mut serializer = Serializer::new(file, Some(PrettyConfig::new().depth_limit(4)))?;
+        self.serialize(&mut serializer)?;
+        info!("saved userdata");
+        Ok(())
+    }
 }
 
 impl Default for UserData {
@@ -37,6 +87,9 @@ impl Default for UserData {
             display_fps: true,
             deadzone_radius: 0.3,
             desired_fps: 60,
+            volume: default_volume(),
+            difficulty: Difficulty::default(),
+            screen_shake: default_screen_shake(),
         }
     }
 }
@@ -2,12 +2,12 @@ use bevy::prelude::*;
 
 use crate::{enemy::Enemy, events::EnemyHit, physics::Physics};
 
-use super::ParticleBundle;
+use super::{ParticleBundle, damage_type_color};
 
 pub fn enemy_hit_effect_system(
     mut commands: Commands,
     mut events: EventReader<EnemyHit>,
-    query: Query<(&Transform, &Physics), With<Enemy>>,
+    query: Query<(&GlobalTransform, Option<&Physics>), With<Enemy>>,
     server: Res<AssetServer>,
 ) {
     // spawn short-lived particles
@@ -16,30 +16,24 @@ pub fn enemy_hit_effect_system(
     }
     for event in events.read() {
         let num_particles = 5;
-        let Ok((
-            transform,
-            Physics {
-                mass: _,
-                velocity,
-                friction: _,
-                gravity: _,
-            },
-        )) = query.get(event.entity)
-        else {
+        let Ok((global_transform, maybe_physics)) = query.get(event.entity) else {
             continue;
         };
+        // enemy parts don't have their own physics
+        let velocity = maybe_physics
+            .map(|physics| physics.velocity)
+            .unwrap_or(Vec3::ZERO);
+        // particles come off where the hit landed, rather than the middle of the enemy
+        let transform = Transform::from_translation(event.position)
+            .with_rotation(global_transform.compute_transform().rotation);
         for _ in 0..num_particles {
             commands
-                .spawn(ParticleBundle::new(
-                    &transform.clone(),
-                    *velocity,
-                    50.0,
-                    0.2,
-                ))
+                .spawn(ParticleBundle::new(&transform, velocity, 50.0, 0.2))
                 .with_children(|builder| {
                     builder.spawn((Sprite {
                         // TODO: replace with randomly chosen particle handle
                         image: server.get_handle("images/bullet.png").unwrap(),
+                        color: damage_type_color(event.source.damage_type),
                         ..Default::default()
 
                     }, Transform::from_scale(Vec3::splat(0.3))
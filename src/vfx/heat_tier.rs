@@ -0,0 +1,46 @@
+use bevy::{color::palettes::css::ORANGE_RED, prelude::*};
+
+use crate::{events::HeatTierChanged, physics::Physics, player::Player};
+
+use super::ParticleBundle;
+
+// burst of particles around the player when the heat tier goes up
+pub fn heat_tier_effect_system(
+    mut commands: Commands,
+    mut events: EventReader<HeatTierChanged>,
+    player: Query<(&Transform, &Physics), With<Player>>,
+    server: Res<AssetServer>,
+) {
+    let Ok((transform, physics)) = player.single() else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        if event.current <= event.previous {
+            continue;
+        }
+        // more particles for hotter tiers
+        let num_particles = 10 + 5 * event.current;
+        let transform = Transform::from_translation(transform.translation);
+        for _ in 0..num_particles {
+            commands
+                .spawn(ParticleBundle::new(
+                    &transform,
+                    physics.velocity,
+                    300.0,
+                    0.6,
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        Sprite {
+                            image: server.get_handle("images/bullet.png").unwrap(),
+                            color: ORANGE_RED.into(),
+                            ..Default::default()
+                        },
+                        Transform::from_scale(Vec3::splat(0.4))
+                            .with_translation(Vec3::new(0.0, 0.0, 2.0)),
+                    ));
+                });
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::{color::palettes::css::YELLOW, prelude::*};
+
+use crate::events::ProjectileIntercepted;
+
+use super::ParticleBundle;
+
+// small spark where a projectile was shot down
+pub fn intercept_effect_system(
+    mut commands: Commands,
+    mut events: EventReader<ProjectileIntercepted>,
+    server: Res<AssetServer>,
+) {
+    for event in events.read() {
+        let num_particles = 8;
+        let transform = Transform::from_translation(event.position);
+        for _ in 0..num_particles {
+            commands
+                .spawn(ParticleBundle::new(&transform, Vec3::ZERO, 120.0, 0.25))
+                .with_children(|builder| {
+                    builder.spawn((
+                        Sprite {
+                            image: server.get_handle("images/bullet.png").unwrap(),
+                            color: YELLOW.into(),
+                            ..Default::default()
+                        },
+                        Transform::from_scale(Vec3::splat(0.25))
+                            .with_translation(Vec3::new(0.0, 0.0, 2.0)),
+                    ));
+                });
+        }
+    }
+}
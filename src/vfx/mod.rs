@@ -4,12 +4,20 @@ use bevy::{prelude::*, sprite::Material2dPlugin};
 use rand::random;
 
 mod enemy_hit;
+mod heat_tier;
 pub mod hp;
+mod intercept;
+mod player_hit;
 
-use crate::{gamestate::GameState, misc::Lifetime, physics::Physics};
+use crate::{
+    events::DamageType, gamestate::GameState, misc::Lifetime, physics::Physics, reset::RunScoped,
+};
 
 use enemy_hit::enemy_hit_effect_system;
+use heat_tier::heat_tier_effect_system;
 use hp::{hp_effect_setup_system, hp_effect_system};
+use intercept::intercept_effect_system;
+use player_hit::player_hit_effect_system;
 
 #[derive(Component)]
 pub struct Particle;
@@ -17,6 +25,7 @@ pub struct Particle;
 #[derive(Bundle)]
 pub struct ParticleBundle {
     particle: Particle,
+    run_scoped: RunScoped,
     transform: Transform,
     physics: Physics,
     lifetime: Lifetime,
@@ -37,19 +46,30 @@ impl ParticleBundle {
         let (sin, cos) = theta.sin_cos();
         ParticleBundle {
             particle: Particle,
-            transform: source_transform.clone(),
+            run_scoped: RunScoped,
+            transform: *source_transform,
             visibility: Visibility::Visible,
             physics: Physics {
                 mass: 0.01,
                 velocity: source_velocity + Vec3::new(r * cos, r * sin, 0.0),
                 friction: 0.999,
                 gravity: Vec3::new(0.0, -2.0, 0.0),
+                ..Default::default()
             },
             lifetime: Lifetime::new(Duration::from_secs_f32(particle_duration_seconds)),
         }
     }
 }
 
+// hit particles are tinted by what kind of damage was done
+pub fn damage_type_color(damage_type: DamageType) -> Color {
+    match damage_type {
+        DamageType::Kinetic => Color::WHITE,
+        DamageType::Explosive => Color::srgb(1.0, 0.6, 0.2),
+        DamageType::Energy => Color::srgb(0.4, 0.9, 1.0),
+    }
+}
+
 pub struct VfxPlugin;
 
 impl Plugin for VfxPlugin {
@@ -61,6 +81,9 @@ impl Plugin for VfxPlugin {
                     hp_effect_setup_system,
                     hp_effect_system,
                     enemy_hit_effect_system,
+                    heat_tier_effect_system,
+                    player_hit_effect_system,
+                    intercept_effect_system,
                 )
                     .run_if(in_state(GameState::InGame)),
             );
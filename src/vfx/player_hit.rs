@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::{events::PlayerHit, physics::Physics, player::Player};
+
+use super::{ParticleBundle, damage_type_color};
+
+// sparks where the player got hit, thrown back towards whatever hit them
+pub fn player_hit_effect_system(
+    mut commands: Commands,
+    mut events: EventReader<PlayerHit>,
+    player: Query<(&Transform, &Physics), With<Player>>,
+    server: Res<AssetServer>,
+) {
+    let Ok((player_transform, physics)) = player.single() else {
+        events.clear();
+        return;
+    };
+    for event in events.read() {
+        // beams hit every frame, so only spark on some of them
+        let num_particles = if event.sustained { 1 } else { 8 };
+        let outward = (event.position - player_transform.translation).normalize_or_zero();
+        let transform = Transform::from_translation(event.position);
+        for _ in 0..num_particles {
+            commands
+                .spawn(ParticleBundle::new(
+                    &transform,
+                    physics.velocity + outward * 150.0,
+                    80.0,
+                    0.25,
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        Sprite {
+                            image: server.get_handle("images/bullet.png").unwrap(),
+                            color: damage_type_color(event.source.damage_type),
+                            ..Default::default()
+                        },
+                        Transform::from_scale(Vec3::splat(0.3))
+                            .with_translation(Vec3::new(0.0, 0.0, 2.0)),
+                    ));
+                });
+        }
+    }
+}